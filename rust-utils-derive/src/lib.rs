@@ -0,0 +1,386 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{Data, DeriveInput, Fields, Ident, LitStr, Type, parse_macro_input, spanned::Spanned};
+
+const INTEGER_REPRS: [&str; 12] = [
+    "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize",
+];
+//
+//      DERIVES
+//
+/// Serializes fields in declaration order. Enums write their discriminant first, using the
+/// integer type from `#[repr(..)]`, or `isize` as the compiler would use. Give a `#[repr(..)]`
+/// to get the same width on every target.
+///
+/// Container attributes:
+/// - `#[byteable(endian = "little" | "big" | "native")]` fixes the byte order of every field.
+///   Without it, the order requested through `copy_bytes_endian` is passed down.
+/// - `#[byteable(padding = "packed" | "c")]` selects no padding (the default) or zero padding
+///   to each field's alignment, as `#[repr(C)]` would lay it out. Every field must be
+///   `bytemuck::Pod` with `"c"`, since variable-length encodings have no `#[repr(C)]` layout.
+#[proc_macro_derive(Byteable, attributes(byteable))]
+pub fn derive_byteable(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_byteable(&input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+
+/// Decodes the layout written by `#[derive(Byteable)]`, honouring the same attributes.
+#[proc_macro_derive(FromBytes, attributes(byteable))]
+pub fn derive_from_bytes(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_from_bytes(&input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+//
+//      OPTIONS
+//
+struct Options {
+    endian: Option<TokenStream2>,
+    c_padding: bool,
+}
+
+fn parse_options(input: &DeriveInput) -> syn::Result<Options> {
+    let mut options = Options {
+        endian: None,
+        c_padding: false,
+    };
+
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("byteable")) {
+        attr.parse_nested_meta(|meta| {
+            let value: LitStr = meta.value()?.parse()?;
+            if meta.path.is_ident("endian") {
+                options.endian = Some(match value.value().as_str() {
+                    "little" => quote!(::rust_utils::generics::Endian::Little),
+                    "big" => quote!(::rust_utils::generics::Endian::Big),
+                    "native" => quote!(::rust_utils::generics::Endian::Native),
+                    _ => return Err(meta.error("expected \"little\", \"big\" or \"native\"")),
+                });
+            } else if meta.path.is_ident("padding") {
+                options.c_padding = match value.value().as_str() {
+                    "packed" => false,
+                    "c" => true,
+                    _ => return Err(meta.error("expected \"packed\" or \"c\"")),
+                };
+            } else {
+                return Err(meta.error("unknown byteable attribute"));
+            }
+            Ok(())
+        })?;
+    }
+
+    Ok(options)
+}
+
+fn repr_type(input: &DeriveInput) -> syn::Result<Ident> {
+    let mut repr = None;
+
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("repr")) {
+        attr.parse_nested_meta(|meta| {
+            if let Some(ident) = meta.path.get_ident()
+                && INTEGER_REPRS.contains(&ident.to_string().as_str())
+            {
+                repr = Some(ident.clone());
+            }
+            Ok(())
+        })?;
+    }
+
+    Ok(repr.unwrap_or_else(|| format_ident!("isize")))
+}
+
+fn field_types(data: &Data) -> Vec<&Type> {
+    match data {
+        Data::Struct(s) => s.fields.iter().map(|f| &f.ty).collect(),
+        Data::Enum(e) => e
+            .variants
+            .iter()
+            .flat_map(|v| v.fields.iter().map(|f| &f.ty))
+            .collect(),
+        Data::Union(_) => vec![],
+    }
+}
+
+/// Bounds every field type by `bound` for generic types, and by `Pod` under C padding. The
+/// `Pod` bound is spanned to the field, so a non-`Pod` field is reported where it is declared.
+fn bounded_generics(input: &DeriveInput, bound: TokenStream2, c_padding: bool) -> syn::Generics {
+    let mut generics = input.generics.clone();
+    let generic = !generics.params.is_empty();
+    let where_clause = generics.make_where_clause();
+    for ty in field_types(&input.data) {
+        if generic {
+            where_clause.predicates.push(syn::parse_quote!(#ty: #bound));
+        }
+        if c_padding {
+            where_clause
+                .predicates
+                .push(syn::parse_quote_spanned! {ty.span()=>
+                    #ty: ::rust_utils::generics::Pod
+                });
+        }
+    }
+    generics
+}
+
+/// Names each field of a variant or struct, returning the binding pattern and the idents.
+fn field_bindings(fields: &Fields) -> (TokenStream2, Vec<Ident>) {
+    let idents: Vec<Ident> = (0..fields.len())
+        .map(|i| format_ident!("__f{}", i))
+        .collect();
+    let pattern = match fields {
+        Fields::Named(named) => {
+            let names = named.named.iter().map(|f| f.ident.as_ref().unwrap());
+            quote!({ #(#names: #idents),* })
+        }
+        Fields::Unnamed(_) => quote!(( #(#idents),* )),
+        Fields::Unit => quote!(),
+    };
+    (pattern, idents)
+}
+
+fn discriminant_consts(input: &DeriveInput, repr: &Ident) -> Vec<(Ident, TokenStream2)> {
+    let Data::Enum(e) = &input.data else {
+        return vec![];
+    };
+
+    let mut consts: Vec<(Ident, TokenStream2)> = vec![];
+    for (i, variant) in e.variants.iter().enumerate() {
+        let name = format_ident!("__D{}", i);
+        let value = match (&variant.discriminant, consts.last()) {
+            (Some((_, expr)), _) => quote!(#expr),
+            (None, Some((prev, _))) => quote!(#prev + 1),
+            (None, None) => quote!(0),
+        };
+        consts.push((name.clone(), quote!(const #name: #repr = #value;)));
+    }
+    consts
+}
+//
+//      BYTEABLE
+//
+fn encode_field(expr: TokenStream2, ty: TokenStream2, c_padding: bool) -> TokenStream2 {
+    let pad = c_padding.then(|| {
+        quote! {
+            let align = ::core::mem::align_of::<#ty>();
+            max_align = max_align.max(align);
+            out.resize(out.len().next_multiple_of(align), 0);
+        }
+    });
+    quote! {
+        #pad
+        out.extend(::rust_utils::generics::Byteable::copy_bytes_endian(#expr, endian));
+    }
+}
+
+fn expand_byteable(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let options = parse_options(input)?;
+    let name = &input.ident;
+    let c_padding = options.c_padding;
+
+    let body = match &input.data {
+        Data::Struct(s) => {
+            let fields = s.fields.iter().enumerate().map(|(i, f)| {
+                let ty = &f.ty;
+                let member = match &f.ident {
+                    Some(ident) => quote!(#ident),
+                    None => {
+                        let index = syn::Index::from(i);
+                        quote!(#index)
+                    }
+                };
+                encode_field(quote!(&self.#member), quote!(#ty), c_padding)
+            });
+            quote!(#(#fields)*)
+        }
+        Data::Enum(e) => {
+            let repr = repr_type(input)?;
+            let consts = discriminant_consts(input, &repr);
+            let const_defs = consts.iter().map(|(_, def)| def);
+            let arms = e.variants.iter().zip(&consts).map(|(variant, (disc, _))| {
+                let ident = &variant.ident;
+                let (pattern, idents) = field_bindings(&variant.fields);
+                let disc = encode_field(quote!(&#disc), quote!(#repr), c_padding);
+                let fields = variant.fields.iter().zip(&idents).map(|(f, binding)| {
+                    let ty = &f.ty;
+                    encode_field(quote!(#binding), quote!(#ty), c_padding)
+                });
+                quote!(Self::#ident #pattern => { #disc #(#fields)* })
+            });
+            quote! {
+                #(#const_defs)*
+                match self {
+                    #(#arms)*
+                }
+            }
+        }
+        Data::Union(_) => {
+            return Err(syn::Error::new(
+                input.span(),
+                "Byteable cannot be derived for unions",
+            ));
+        }
+    };
+
+    let endian = options.endian.unwrap_or_else(|| quote!(endian));
+    let trailing_pad =
+        c_padding.then(|| quote!(out.resize(out.len().next_multiple_of(max_align), 0);));
+
+    let generics = bounded_generics(input, quote!(::rust_utils::generics::Byteable), c_padding);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::rust_utils::generics::Byteable for #name #ty_generics #where_clause {
+            fn to_bytes(self) -> ::std::vec::Vec<u8> {
+                ::rust_utils::generics::Byteable::copy_bytes(&self)
+            }
+
+            fn copy_bytes(&self) -> ::std::vec::Vec<u8> {
+                ::rust_utils::generics::Byteable::copy_bytes_endian(
+                    self,
+                    ::rust_utils::generics::Endian::Native,
+                )
+            }
+
+            #[allow(unused_mut, unused_variables)]
+            fn copy_bytes_endian(
+                &self,
+                endian: ::rust_utils::generics::Endian,
+            ) -> ::std::vec::Vec<u8> {
+                let endian = #endian;
+                let mut out = ::std::vec::Vec::new();
+                let mut max_align = 1usize;
+                #body
+                #trailing_pad
+                out
+            }
+        }
+    })
+}
+//
+//      FROMBYTES
+//
+fn decode_field(binding: &Ident, ty: TokenStream2, c_padding: bool) -> TokenStream2 {
+    let pad = c_padding.then(|| {
+        quote! {
+            let align = ::core::mem::align_of::<#ty>();
+            max_align = max_align.max(align);
+            let consumed = bytes.len() - rest.len();
+            let pad = consumed.next_multiple_of(align) - consumed;
+            if rest.len() < pad {
+                return Err(::rust_utils::generics::ByteError::LengthError {
+                    expected: pad,
+                    found: rest.len(),
+                });
+            }
+            rest = &rest[pad..];
+        }
+    });
+    quote! {
+        #pad
+        let (#binding, r) =
+            <#ty as ::rust_utils::generics::FromBytes>::read_bytes_endian(rest, endian)?;
+        rest = r;
+    }
+}
+
+fn expand_from_bytes(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let options = parse_options(input)?;
+    let name = &input.ident;
+    let c_padding = options.c_padding;
+
+    let body = match &input.data {
+        Data::Struct(s) => {
+            let (pattern, idents) = field_bindings(&s.fields);
+            let fields = s.fields.iter().zip(&idents).map(|(f, binding)| {
+                let ty = &f.ty;
+                decode_field(binding, quote!(#ty), c_padding)
+            });
+            quote! {
+                #(#fields)*
+                let value = Self #pattern;
+            }
+        }
+        Data::Enum(e) => {
+            let repr = repr_type(input)?;
+            let consts = discriminant_consts(input, &repr);
+            let const_defs = consts.iter().map(|(_, def)| def);
+            let disc = decode_field(&format_ident!("__disc"), quote!(#repr), c_padding);
+            let branches = e.variants.iter().zip(&consts).map(|(variant, (d, _))| {
+                let ident = &variant.ident;
+                let (pattern, idents) = field_bindings(&variant.fields);
+                let fields = variant.fields.iter().zip(&idents).map(|(f, binding)| {
+                    let ty = &f.ty;
+                    decode_field(binding, quote!(#ty), c_padding)
+                });
+                quote! {
+                    if __disc == #d {
+                        #(#fields)*
+                        Self::#ident #pattern
+                    } else
+                }
+            });
+            quote! {
+                #(#const_defs)*
+                #disc
+                let value = #(#branches)* {
+                    return Err(::rust_utils::generics::ByteError::DiscriminantError(
+                        __disc as i128,
+                    ));
+                };
+            }
+        }
+        Data::Union(_) => {
+            return Err(syn::Error::new(
+                input.span(),
+                "FromBytes cannot be derived for unions",
+            ));
+        }
+    };
+
+    let endian = options.endian.unwrap_or_else(|| quote!(endian));
+    let trailing_pad = c_padding.then(|| {
+        quote! {
+            let consumed = bytes.len() - rest.len();
+            let pad = consumed.next_multiple_of(max_align) - consumed;
+            if rest.len() < pad {
+                return Err(::rust_utils::generics::ByteError::LengthError {
+                    expected: pad,
+                    found: rest.len(),
+                });
+            }
+            rest = &rest[pad..];
+        }
+    });
+
+    let generics = bounded_generics(input, quote!(::rust_utils::generics::FromBytes), c_padding);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::rust_utils::generics::FromBytes for #name #ty_generics #where_clause {
+            fn read_bytes(
+                bytes: &[u8],
+            ) -> ::core::result::Result<(Self, &[u8]), ::rust_utils::generics::ByteError> {
+                <Self as ::rust_utils::generics::FromBytes>::read_bytes_endian(
+                    bytes,
+                    ::rust_utils::generics::Endian::Native,
+                )
+            }
+
+            #[allow(unused_mut, unused_variables)]
+            fn read_bytes_endian(
+                bytes: &[u8],
+                endian: ::rust_utils::generics::Endian,
+            ) -> ::core::result::Result<(Self, &[u8]), ::rust_utils::generics::ByteError> {
+                let endian = #endian;
+                let mut rest = bytes;
+                let mut max_align = 1usize;
+                #body
+                #trailing_pad
+                Ok((value, rest))
+            }
+        }
+    })
+}
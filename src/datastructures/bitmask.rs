@@ -1,3 +1,4 @@
+use crate::generics::{ByteError, Byteable, FromBytes};
 //
 //      MACROS
 //
@@ -35,22 +36,31 @@ impl<const BLEN: usize> BitMask<BLEN> {
 
 impl<const BLEN: usize> From<String> for BitMask<BLEN> {
     fn from(x: String) -> BitMask<BLEN> {
-        let mut end: [u8; BLEN] = [0; BLEN];
-        let c = Vec::from_iter(x.chars().enumerate());
-        let split = Vec::from_iter(c.split(|x| x.0 % 8 == 0));
-
-        let mut final_bytes: Vec<u8> = vec![];
-
-        for i in split {
-            let chars = Vec::from_iter(i.iter().map(|x| x.1));
-            let byte: u8 = chars
-                .iter()
-                .fold(0u8, |acc, &c| (acc << 1) | (c == '1') as u8);
-            final_bytes.push(byte);
-        }
+        let chars = Vec::from_iter(x.chars());
+
+        let final_bytes = Vec::from_iter(chars.chunks(8).map(|byte| {
+            byte.iter()
+                .fold(0u8, |acc, &c| (acc << 1) | (c == '1') as u8)
+        }));
 
         BitMask {
             mask: final_bytes.try_into().expect(" ... "),
         }
     }
 }
+
+impl<const BLEN: usize> Byteable for BitMask<BLEN> {
+    fn to_bytes(self) -> Vec<u8> {
+        self.mask.to_vec()
+    }
+    fn copy_bytes(&self) -> Vec<u8> {
+        self.mask.to_vec()
+    }
+}
+
+impl<const BLEN: usize> FromBytes for BitMask<BLEN> {
+    fn read_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), ByteError> {
+        let (mask, rest) = <[u8; BLEN]>::read_bytes(bytes)?;
+        Ok((BitMask { mask }, rest))
+    }
+}
@@ -2,7 +2,12 @@ use std::{marker::PhantomData, mem};
 
 use thiserror::Error;
 
-use crate::{bmask, datastructures::BitMask, flag_check, generics::Byteable};
+use crate::{
+    bmask,
+    datastructures::BitMask,
+    flag_check,
+    generics::{ByteError, Byteable, FromBytes},
+};
 
 const MANAGED_FLAG: u8 = 0b1000_0000;
 //
@@ -32,9 +37,15 @@ pub struct ByteHeap<const SIZE: usize> {
 //      STRUCT IMPLS
 //
 impl<A: Byteable + bytemuck::Pod> Accessor<'_, A> {
-    pub fn retrieve(&self) -> &A {
-        let x: &[A] = bytemuck::cast_slice(self.value);
-        &x[0]
+    /// Copies the value out, since heap slots carry no alignment.
+    pub fn retrieve(&self) -> A {
+        bytemuck::pod_read_unaligned(self.value)
+    }
+}
+
+impl<A: FromBytes> Accessor<'_, A> {
+    pub fn decode(&self) -> Result<A, ByteError> {
+        A::from_bytes(self.value)
     }
 }
 
@@ -61,11 +72,8 @@ impl<const S: usize> ByteHeap<S> {
 
         Err(E::AllocError)
     }
-    //
-    //      RAW FNS
-    //
-    pub fn raw_insert<A: Byteable>(&mut self, item: A) -> Result<usize, E> {
-        let bytes = item.to_bytes();
+
+    fn insert_bytes(&mut self, bytes: &[u8]) -> Result<usize, E> {
         let index = self.find_available(bytes.len())?;
 
         let insertion_region = self
@@ -75,11 +83,17 @@ impl<const S: usize> ByteHeap<S> {
 
         for (x, i) in insertion_region.iter_mut().enumerate() {
             *i = bytes[x];
-            self.allocation_flags[x] = bmask!("10000000")
+            self.allocation_flags[index + x] = bmask!("10000000")
         }
 
         Ok(index)
     }
+    //
+    //      RAW FNS
+    //
+    pub fn raw_insert<A: Byteable>(&mut self, item: A) -> Result<usize, E> {
+        self.insert_bytes(&item.to_bytes())
+    }
 
     pub fn raw_retrieve(&self, range: std::ops::Range<usize>) -> &[u8] {
         &self.bytes[range]
@@ -88,10 +102,10 @@ impl<const S: usize> ByteHeap<S> {
     //
     //
     pub fn insert<'a, A: Byteable>(&'a mut self, item: A) -> Result<Accessor<'a, A>, E> {
-        let i = self.raw_insert(item)?;
-        let l = mem::size_of::<A>();
+        let bytes = item.to_bytes();
+        let i = self.insert_bytes(&bytes)?;
 
-        let r = self.raw_retrieve(i..l);
+        let r = self.raw_retrieve(i..i + bytes.len());
 
         Ok(Accessor {
             value: r,
@@ -102,11 +116,7 @@ impl<const S: usize> ByteHeap<S> {
     //      CONSTRUCTOR
     //
     pub fn new() -> ByteHeap<S> {
-        let mut masks = vec![];
-
-        for i in 0..S {
-            masks[i] = bmask!("00000000");
-        }
+        let masks: Vec<BitMask<1>> = (0..S).map(|_| bmask!("00000000")).collect();
 
         ByteHeap {
             bytes: Box::new([0; S]),
@@ -122,3 +132,27 @@ impl<const S: usize> Default for ByteHeap<S> {
         Self::new()
     }
 }
+//
+//      TESTS
+//
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retrieves_unaligned_values() {
+        let mut heap = ByteHeap::<64>::new();
+        assert_eq!(heap.insert(1u8).unwrap().retrieve(), 1);
+        assert_eq!(heap.insert(2.5f32).unwrap().retrieve(), 2.5);
+        assert_eq!(heap.insert(u64::MAX - 1).unwrap().retrieve(), u64::MAX - 1);
+        assert_eq!(heap.raw_retrieve(0..1), [1]);
+    }
+
+    #[test]
+    fn decodes_variable_length_values() {
+        let mut heap = ByteHeap::<64>::new();
+        heap.insert(7u16).unwrap();
+        let text = String::from("unaligned");
+        assert_eq!(heap.insert(text.clone()).unwrap().decode(), Ok(text));
+    }
+}
@@ -38,7 +38,7 @@ impl<const BYTELEN: usize, V> ByteMap<BYTELEN, V> {
     fn generate_key(&mut self) -> Result<[u8; BYTELEN], E> {
         if self.occupied_keys < self.capacity {
             let key = self.occupied_keys + 1;
-            let key_b: [u8; BYTELEN] = usize::to_ne_bytes(key)[0..BYTELEN]
+            let key_b: [u8; BYTELEN] = usize::to_le_bytes(key)[0..BYTELEN]
                 .try_into()
                 .map_err(|z| E::CastError)?;
             self.occupied_keys += 1;
@@ -63,7 +63,7 @@ impl<const BYTELEN: usize, V> ByteMap<BYTELEN, V> {
     //      CONSTRUCTOR
     //
     pub fn new() -> Self {
-        let cap = (2 as usize).pow(BYTELEN as u32 * 8) - 1;
+        let cap = 2usize.pow(BYTELEN as u32 * 8) - 1;
         Self {
             map: HashMap::new(),
             capacity: cap,
@@ -71,3 +71,11 @@ impl<const BYTELEN: usize, V> ByteMap<BYTELEN, V> {
         }
     }
 }
+//
+//      DEFAULT IMPLS
+//
+impl<const BYTELEN: usize, V> Default for ByteMap<BYTELEN, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,7 +1,16 @@
-use std::{fmt::Debug, ops::Deref};
+use std::{fmt::Debug, ops::Deref, str::FromStr};
 
 use thiserror::Error;
 
+use crate::generics::{ByteError, Byteable, FromBytes, Varint};
+
+const FORMAT_VERSION: u8 = 1;
+const INDEX_FLAG: u8 = 0b0000_0001;
+const CHILDREN_FLAG: u8 = 0b0000_0010;
+// deeper input is rejected rather than risking the stack while decoding or dropping it
+const MAX_DEPTH: usize = 512;
+
+#[allow(clippy::enum_variant_names)]
 #[derive(Error, Debug)]
 pub enum IndexNodeError {
     #[error("Failed to get associated item.")]
@@ -10,6 +19,10 @@ pub enum IndexNodeError {
     NoAssociationError,
     #[error("Could not associate node.")]
     CouldNotAssociateError,
+    #[error("Unsupported index tree format version {0}.")]
+    FormatVersionError(usize),
+    #[error("Malformed index tree input at position {0}.")]
+    MalformedInputError(usize),
 }
 
 pub enum IndexNode<A> {
@@ -17,7 +30,7 @@ pub enum IndexNode<A> {
     NotAssociated(NodeData),
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct NodeData {
     index: Option<usize>, //only leaf nodes have an index
     children: Option<Vec<NodeData>>,
@@ -31,17 +44,267 @@ impl NodeData {
     pub fn index(&self) -> Option<usize> {
         self.index
     }
+    //------------------------------------------------------------------------------------------
+    /// Decodes the whole of `bytes` as written by `Byteable::to_bytes`. `FromBytes` reads the
+    /// same format, reporting these errors as `ByteError`s.
+    pub fn decode(bytes: &[u8]) -> Result<NodeData, IndexNodeError> {
+        let (node, pos) = Self::read_tree(bytes)?;
+        if pos != bytes.len() {
+            return Err(IndexNodeError::MalformedInputError(pos));
+        }
+        Ok(node)
+    }
+
+    /// Reads a version byte and one tree, returning it with the position after it.
+    fn read_tree(bytes: &[u8]) -> Result<(NodeData, usize), IndexNodeError> {
+        let version = *bytes
+            .first()
+            .ok_or(IndexNodeError::MalformedInputError(0))?;
+        if version != FORMAT_VERSION {
+            return Err(IndexNodeError::FormatVersionError(version as usize));
+        }
+
+        let mut pos = 1;
+        let node = Self::decode_node(bytes, &mut pos, 0)?;
+        Ok((node, pos))
+    }
+
+    fn encode(&self, out: &mut Vec<u8>) {
+        let mut flags = 0;
+        if self.index.is_some() {
+            flags |= INDEX_FLAG;
+        }
+        if self.children.is_some() {
+            flags |= CHILDREN_FLAG;
+        }
+        out.push(flags);
+
+        if let Some(index) = self.index {
+            out.extend(Varint(index).copy_bytes());
+        }
+        if let Some(children) = &self.children {
+            out.extend(Varint(children.len()).copy_bytes());
+            for child in children {
+                child.encode(out);
+            }
+        }
+    }
+
+    fn decode_node(
+        bytes: &[u8],
+        pos: &mut usize,
+        depth: usize,
+    ) -> Result<NodeData, IndexNodeError> {
+        let flags = *bytes
+            .get(*pos)
+            .ok_or(IndexNodeError::MalformedInputError(*pos))?;
+        if flags & !(INDEX_FLAG | CHILDREN_FLAG) != 0 || depth > MAX_DEPTH {
+            return Err(IndexNodeError::MalformedInputError(*pos));
+        }
+        *pos += 1;
+
+        let index = if flags & INDEX_FLAG != 0 {
+            Some(read_varint(bytes, pos)?)
+        } else {
+            None
+        };
+
+        let children = if flags & CHILDREN_FLAG != 0 {
+            let count = read_varint(bytes, pos)?;
+            // every child takes at least one byte, so larger counts can't be valid
+            if count > bytes.len() - *pos {
+                return Err(IndexNodeError::MalformedInputError(*pos));
+            }
+            let mut children = Vec::with_capacity(count);
+            for _ in 0..count {
+                children.push(Self::decode_node(bytes, pos, depth + 1)?);
+            }
+            Some(children)
+        } else {
+            None
+        };
+
+        Ok(NodeData { index, children })
+    }
+
+    fn write_text(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self.index {
+            Some(index) => write!(f, "{index}")?,
+            None => write!(f, "_")?,
+        }
+
+        if let Some(children) = &self.children {
+            write!(f, "[")?;
+            for (i, child) in children.iter().enumerate() {
+                if i > 0 {
+                    write!(f, ", ")?;
+                }
+                child.write_text(f)?;
+            }
+            write!(f, "]")?;
+        }
+        Ok(())
+    }
+}
+
+impl Byteable for NodeData {
+    fn to_bytes(self) -> Vec<u8> {
+        self.copy_bytes()
+    }
+
+    fn copy_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![FORMAT_VERSION];
+        self.encode(&mut bytes);
+        bytes
+    }
+}
+
+/// Reads a version byte followed by the nodes in pre-order, each as a flag byte, an optional
+/// varint index and an optional varint child count.
+impl FromBytes for NodeData {
+    fn read_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), ByteError> {
+        match Self::read_tree(bytes) {
+            Ok((node, pos)) => Ok((node, &bytes[pos..])),
+            Err(IndexNodeError::FormatVersionError(version)) => {
+                Err(ByteError::VersionError(version as u8))
+            }
+            Err(IndexNodeError::MalformedInputError(pos)) => Err(ByteError::MalformedError(pos)),
+            Err(_) => unreachable!("decoding only reports version and input errors"),
+        }
+    }
+}
+
+/// Text form, e.g. `v1 _[0, 1, _[2, 3[]]]`. `_` marks a node without an index and a
+/// bracketed list follows any node that has a (possibly empty) child list.
+impl std::fmt::Display for NodeData {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "v{FORMAT_VERSION} ")?;
+        self.write_text(f)
+    }
+}
+
+impl FromStr for NodeData {
+    type Err = IndexNodeError;
+
+    fn from_str(s: &str) -> Result<NodeData, IndexNodeError> {
+        let mut parser = TextParser {
+            bytes: s.as_bytes(),
+            pos: 0,
+        };
+
+        parser.skip_whitespace();
+        parser.expect(b'v')?;
+        let version = parser.number()?;
+        if version != FORMAT_VERSION as usize {
+            return Err(IndexNodeError::FormatVersionError(version));
+        }
+
+        let node = parser.node(0)?;
+        parser.skip_whitespace();
+        if parser.pos != parser.bytes.len() {
+            return Err(IndexNodeError::MalformedInputError(parser.pos));
+        }
+        Ok(node)
+    }
+}
+//------------------------------------------------------------------------------------------
+struct TextParser<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl TextParser<'_> {
+    fn skip_whitespace(&mut self) {
+        while self
+            .bytes
+            .get(self.pos)
+            .is_some_and(u8::is_ascii_whitespace)
+        {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&mut self) -> Option<u8> {
+        self.skip_whitespace();
+        self.bytes.get(self.pos).copied()
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), IndexNodeError> {
+        if self.peek() != Some(byte) {
+            return Err(IndexNodeError::MalformedInputError(self.pos));
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    fn number(&mut self) -> Result<usize, IndexNodeError> {
+        let start = self.pos;
+        while self.bytes.get(self.pos).is_some_and(u8::is_ascii_digit) {
+            self.pos += 1;
+        }
+
+        std::str::from_utf8(&self.bytes[start..self.pos])
+            .ok()
+            .and_then(|digits| digits.parse().ok())
+            .ok_or(IndexNodeError::MalformedInputError(start))
+    }
+
+    fn node(&mut self, depth: usize) -> Result<NodeData, IndexNodeError> {
+        if depth > MAX_DEPTH {
+            return Err(IndexNodeError::MalformedInputError(self.pos));
+        }
+        let index = match self.peek() {
+            Some(b'_') => {
+                self.pos += 1;
+                None
+            }
+            _ => Some(self.number()?),
+        };
+
+        let children = if self.peek() == Some(b'[') {
+            self.pos += 1;
+            let mut children = vec![];
+            if self.peek() == Some(b']') {
+                self.pos += 1;
+            } else {
+                loop {
+                    children.push(self.node(depth + 1)?);
+                    match self.peek() {
+                        Some(b',') => self.pos += 1,
+                        Some(b']') => {
+                            self.pos += 1;
+                            break;
+                        }
+                        _ => return Err(IndexNodeError::MalformedInputError(self.pos)),
+                    }
+                }
+            }
+            Some(children)
+        } else {
+            None
+        };
+
+        Ok(NodeData { index, children })
+    }
+}
+
+fn read_varint(bytes: &[u8], pos: &mut usize) -> Result<usize, IndexNodeError> {
+    let input = &bytes[*pos..];
+    let (Varint(value), rest) = Varint::<usize>::read_bytes(input)
+        .map_err(|_| IndexNodeError::MalformedInputError(*pos))?;
+    *pos += input.len() - rest.len();
+    Ok(value)
 }
 
 impl<A> IndexNode<A> {
     //------------------------------------------------------------------------------------------
-    fn data(&self) -> &NodeData {
+    pub fn data(&self) -> &NodeData {
         match self {
             IndexNode::Associated(x, _) | IndexNode::NotAssociated(x) => x,
         }
     }
 
-    fn into_data(self) -> NodeData {
+    pub fn into_data(self) -> NodeData {
         match self {
             IndexNode::Associated(x, _) | IndexNode::NotAssociated(x) => x,
         }
@@ -89,15 +352,23 @@ impl<A> IndexNode<A> {
     }
 }
 
+impl<A> From<NodeData> for IndexNode<A> {
+    fn from(data: NodeData) -> IndexNode<A> {
+        IndexNode::NotAssociated(data)
+    }
+}
+
 impl<A: Clone + Debug> std::fmt::Debug for IndexNode<A> {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
         f.debug_struct("IndexNode")
             .field("Associated", &self.is_associated())
             .field(
                 "Associated Item",
-                &(self.is_associated())
-                    .then(|| -> String { format!("{:?}", self.associated().unwrap()) })
-                    .unwrap_or_else(|| -> String { String::from("N/A") }),
+                &if self.is_associated() {
+                    format!("{:?}", self.associated().unwrap())
+                } else {
+                    String::from("N/A")
+                },
             )
             .field("Children", &self.children().iter().len())
             .finish();
@@ -105,3 +376,133 @@ impl<A: Clone + Debug> std::fmt::Debug for IndexNode<A> {
         Ok(())
     }
 }
+//
+//      TESTS
+//
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leaf(index: usize) -> NodeData {
+        NodeData {
+            index: Some(index),
+            children: None,
+        }
+    }
+
+    fn branch(index: Option<usize>, children: Vec<NodeData>) -> NodeData {
+        NodeData {
+            index,
+            children: Some(children),
+        }
+    }
+
+    fn sample() -> NodeData {
+        branch(
+            None,
+            vec![
+                leaf(0),
+                leaf(300),
+                branch(None, vec![leaf(2), branch(Some(3), vec![])]),
+                leaf(usize::MAX),
+            ],
+        )
+    }
+
+    #[test]
+    fn binary_round_trips() {
+        for node in [sample(), leaf(7), branch(None, vec![])] {
+            let bytes = node.copy_bytes();
+            assert_eq!(NodeData::decode(&bytes).unwrap(), node);
+            assert_eq!(NodeData::from_bytes(&bytes).unwrap(), node);
+        }
+    }
+
+    #[test]
+    fn text_round_trips() {
+        let node = sample();
+        let text = node.to_string();
+        assert_eq!(text, format!("v1 _[0, 300, _[2, 3[]], {}]", usize::MAX));
+        assert_eq!(text.parse::<NodeData>().unwrap(), node);
+        assert_eq!(
+            " v1 _ [ 1 ,2[ ] ] "
+                .parse::<NodeData>()
+                .unwrap()
+                .to_string(),
+            "v1 _[1, 2[]]"
+        );
+    }
+
+    #[test]
+    fn binary_errors() {
+        let mut bytes = sample().copy_bytes();
+        bytes[0] = 2;
+        assert!(matches!(
+            NodeData::decode(&bytes),
+            Err(IndexNodeError::FormatVersionError(2))
+        ));
+        assert_eq!(
+            NodeData::from_bytes(&bytes),
+            Err(ByteError::VersionError(2))
+        );
+
+        let bytes = sample().copy_bytes();
+        let truncated = &bytes[..bytes.len() - 1];
+        assert!(matches!(
+            NodeData::decode(truncated),
+            Err(IndexNodeError::MalformedInputError(_))
+        ));
+        assert!(matches!(
+            NodeData::decode(&[bytes.as_slice(), &[0]].concat()),
+            Err(IndexNodeError::MalformedInputError(pos)) if pos == bytes.len()
+        ));
+        assert!(matches!(
+            NodeData::decode(&[FORMAT_VERSION, 0b100]),
+            Err(IndexNodeError::MalformedInputError(1))
+        ));
+        assert!(matches!(
+            NodeData::decode(&[]),
+            Err(IndexNodeError::MalformedInputError(0))
+        ));
+    }
+
+    #[test]
+    fn text_errors() {
+        assert!(matches!(
+            "v300 1".parse::<NodeData>(),
+            Err(IndexNodeError::FormatVersionError(300))
+        ));
+        for text in ["", "1", "v1", "v1 1[", "v1 1[2,]", "v1 1 2", "v1 x"] {
+            assert!(
+                matches!(
+                    text.parse::<NodeData>(),
+                    Err(IndexNodeError::MalformedInputError(_))
+                ),
+                "{text:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn deep_input_is_rejected() {
+        let depth = 200_000;
+        let text = format!("v1 {}{}", "_[".repeat(depth), "]".repeat(depth));
+        assert!(matches!(
+            text.parse::<NodeData>(),
+            Err(IndexNodeError::MalformedInputError(_))
+        ));
+
+        let mut bytes = vec![FORMAT_VERSION];
+        for _ in 0..depth {
+            bytes.extend([CHILDREN_FLAG, 1]);
+        }
+        bytes.push(0);
+        assert!(matches!(
+            NodeData::decode(&bytes),
+            Err(IndexNodeError::MalformedInputError(_))
+        ));
+
+        let nested = format!("v1 {}{}", "_[".repeat(MAX_DEPTH), "]".repeat(MAX_DEPTH));
+        assert_eq!(nested.parse::<NodeData>().unwrap().to_string(), nested);
+    }
+}
@@ -6,4 +6,4 @@ mod index_tree;
 pub use bitmask::BitMask;
 pub use byte_heap::ByteHeap;
 pub use byte_map::ByteMap;
-pub use index_tree::IndexNode;
+pub use index_tree::{IndexNode, IndexNodeError, NodeData};
@@ -1,4 +1,3 @@
 pub mod string_ext;
 
-pub trait StringExt {}
-impl StringExt for String {}
+pub use string_ext::{DelimiterError, DelimiterSet, StringExt};
@@ -1,39 +1,775 @@
+use std::ops::ControlFlow;
+
+use thiserror::Error;
+use unicode_segmentation::UnicodeSegmentation;
+//
+//      ERRORS
+//
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum DelimiterError {
+    #[error("Unexpected closing delimiter '{found}' at byte {position}.")]
+    UnexpectedCloseError { found: char, position: usize },
+    #[error("Delimiter '{open}' at byte {open_position} closed by '{found}' at byte {position}.")]
+    MismatchError {
+        open: char,
+        open_position: usize,
+        found: char,
+        position: usize,
+    },
+    #[error("Delimiter '{open}' at byte {position} is never closed.")]
+    UnclosedError { open: char, position: usize },
+    #[error("Quote '{quote}' at byte {position} is never closed.")]
+    UnterminatedQuoteError { quote: char, position: usize },
+}
+use DelimiterError as E;
+//
+//      STRUCTS
+//
+/// The delimiter pairs, quote characters and escape character a scan understands.
+///
+/// Quoted regions are skipped whole, and a quote is closed by the same character that opened
+/// it. The escape character only has meaning inside quotes, where it skips the character after
+/// it. Open and close characters should all be distinct from each other and from the quotes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DelimiterSet {
+    pairs: Vec<(char, char)>,
+    quotes: Vec<char>,
+    escape: Option<char>,
+}
+
+impl DelimiterSet {
+    /// The given pairs, with no quotes or escape.
+    pub fn new(pairs: &[(char, char)]) -> Self {
+        Self {
+            pairs: pairs.to_vec(),
+            quotes: vec![],
+            escape: None,
+        }
+    }
+
+    pub fn with_quotes(mut self, quotes: &[char]) -> Self {
+        self.quotes = quotes.to_vec();
+        self
+    }
+
+    pub fn with_escape(mut self, escape: Option<char>) -> Self {
+        self.escape = escape;
+        self
+    }
+
+    pub fn closing(&self, open: char) -> Option<char> {
+        self.pairs
+            .iter()
+            .find(|&&(o, _)| o == open)
+            .map(|&(_, c)| c)
+    }
+
+    pub fn is_closing(&self, c: char) -> bool {
+        self.pairs.iter().any(|&(_, close)| close == c)
+    }
+
+    pub fn is_quote(&self, c: char) -> bool {
+        self.quotes.contains(&c)
+    }
+
+    /// Walks `text` from `start_index`, calling `visit` with the byte offset, character and
+    /// nesting depth of everything outside quotes. Delimiters are visited at the depth outside
+    /// them, so the top level is always depth 0. Errors are only reported for the part of the
+    /// text scanned before `visit` breaks.
+    fn scan(
+        &self,
+        text: &str,
+        start_index: usize,
+        mut visit: impl FnMut(usize, char, usize) -> ControlFlow<()>,
+    ) -> Result<(), DelimiterError> {
+        let Some(rest) = text.get(start_index..) else {
+            return Ok(());
+        };
+        let mut open: Vec<(char, char, usize)> = vec![];
+        let mut quote: Option<(char, usize)> = None;
+        let mut escaped = false;
+
+        for (i, c) in rest.char_indices() {
+            let position = start_index + i;
+
+            if let Some((q, _)) = quote {
+                if escaped {
+                    escaped = false;
+                } else if Some(c) == self.escape {
+                    escaped = true;
+                } else if c == q {
+                    quote = None;
+                }
+                continue;
+            }
+
+            if self.is_quote(c) {
+                quote = Some((c, position));
+            } else if let Some(close) = self.closing(c) {
+                if visit(position, c, open.len()).is_break() {
+                    return Ok(());
+                }
+                open.push((c, close, position));
+                continue;
+            } else if self.is_closing(c) {
+                match open.pop() {
+                    Some((_, close, _)) if close == c => {}
+                    Some((o, _, open_position)) => {
+                        return Err(E::MismatchError {
+                            open: o,
+                            open_position,
+                            found: c,
+                            position,
+                        });
+                    }
+                    None => return Err(E::UnexpectedCloseError { found: c, position }),
+                }
+            }
+            if visit(position, c, open.len()).is_break() {
+                return Ok(());
+            }
+        }
+
+        if let Some((q, position)) = quote {
+            return Err(E::UnterminatedQuoteError { quote: q, position });
+        }
+        match open.pop() {
+            Some((o, _, position)) => Err(E::UnclosedError { open: o, position }),
+            None => Ok(()),
+        }
+    }
+}
+
+/// `()`, `[]` and `{}`, with `"` quotes and a `\` escape. `'` isn't a quote by default, as
+/// apostrophes in prose would open one; add it with `with_quotes` for languages that need it.
+impl Default for DelimiterSet {
+    fn default() -> Self {
+        Self::new(&[('(', ')'), ('[', ']'), ('{', '}')])
+            .with_quotes(&['"'])
+            .with_escape(Some('\\'))
+    }
+}
+//
+//      PRIVATE
+//
+fn words(text: &str) -> Vec<&str> {
+    let mut words = vec![];
+
+    for part in text.split(|c: char| !c.is_alphanumeric()) {
+        let chars: Vec<(usize, char)> = part.char_indices().collect();
+        let mut word_start = 0;
+
+        for (n, &(i, c)) in chars.iter().enumerate().skip(1) {
+            let prev = chars[n - 1].1;
+            let next_is_lower = chars.get(n + 1).is_some_and(|&(_, c)| c.is_lowercase());
+            let lower_to_upper = !prev.is_uppercase() && c.is_uppercase();
+            let acronym_end = prev.is_uppercase() && c.is_uppercase() && next_is_lower;
+
+            if lower_to_upper || acronym_end {
+                words.push(&part[word_start..i]);
+                word_start = i;
+            }
+        }
+        if word_start < part.len() {
+            words.push(&part[word_start..]);
+        }
+    }
+    words
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first
+            .to_uppercase()
+            .chain(chars.flat_map(char::to_lowercase))
+            .collect(),
+        None => String::new(),
+    }
+}
+
+fn join_lowercase(text: &str, sep: &str) -> String {
+    words(text)
+        .iter()
+        .map(|word| word.to_lowercase())
+        .collect::<Vec<_>>()
+        .join(sep)
+}
+//
+//      TRAITS
+//
+/// Searches return byte offsets into the string, so they can be used to slice it directly.
+/// A `start_index` past the end or inside a multi-byte character finds nothing.
 pub trait StringExt {
-    fn find_next_char_index(&self, start_index: usize, target: char) -> usize;
-    fn find_next_delimiter_index(&self, start_index: usize, delimiter_pair: (char, char)) -> usize;
+    /// Byte offset of the first `target` at or after `start_index`.
+    fn find_next_char_index(&self, start_index: usize, target: char) -> Option<usize>;
+    /// Byte offset of the delimiter closing the first `delimiter_pair.0` at or after
+    /// `start_index`. Nested pairs are skipped over, and `None` means the opening delimiter
+    /// is missing or never closed.
+    fn find_next_delimiter_index(
+        &self,
+        start_index: usize,
+        delimiter_pair: (char, char),
+    ) -> Option<usize>;
+    /// Byte offset of the delimiter closing the first opening delimiter at or after
+    /// `start_index`, outside quotes. `Ok(None)` means there is no opening delimiter.
+    fn find_matching_delimiter(
+        &self,
+        start_index: usize,
+        delimiters: &DelimiterSet,
+    ) -> Result<Option<usize>, DelimiterError>;
+    /// Checks that every delimiter and quote in the string is closed, and closed in order.
+    fn check_balanced(&self, delimiters: &DelimiterSet) -> Result<(), DelimiterError>;
+    /// Splits on every `sep` outside delimiters and quotes. An empty string gives one empty
+    /// piece, as with `str::split`.
+    fn split_top_level(
+        &self,
+        sep: char,
+        delimiters: &DelimiterSet,
+    ) -> Result<Vec<&str>, DelimiterError>;
+    /// Splits on the first `sep` outside delimiters and quotes, still checking the whole
+    /// string is balanced.
+    fn split_top_level_once(
+        &self,
+        sep: char,
+        delimiters: &DelimiterSet,
+    ) -> Result<Option<(&str, &str)>, DelimiterError>;
+
+    /// Case conversions split words on anything that isn't alphanumeric, on a lower-to-upper
+    /// change (`fooBar`) and before the last capital of an acronym (`HTTPServer`). Digits stay
+    /// with the word before them.
+    fn to_snake_case(&self) -> String;
+    fn to_kebab_case(&self) -> String;
+    fn to_camel_case(&self) -> String;
+    fn to_pascal_case(&self) -> String;
+
+    /// Removes leading whitespace and then `margin` from every line that has one. Other lines
+    /// are left alone.
+    fn strip_margin(&self, margin: char) -> String;
+    /// Removes the whitespace prefix shared by every non-blank line. Blank lines are emptied,
+    /// and line endings are kept.
+    fn dedent(&self) -> String;
+    /// Cuts the string to at most `max_graphemes` grapheme clusters, ending in `ellipsis` when
+    /// anything was removed. The ellipsis is itself cut if it doesn't fit.
+    fn truncate_graphemes(&self, max_graphemes: usize, ellipsis: &str) -> String;
+    /// The 1-based line and column of a byte offset, counting columns in characters. The end
+    /// of the string is a valid offset, and offsets inside a character give `None`.
+    fn line_col_at(&self, byte_offset: usize) -> Option<(usize, usize)>;
 }
 
-impl StringExt for String {
-    fn find_next_char_index(&self, start_index: usize, target: char) -> usize {
-        let mut chars = self[start_index..self.len()].chars();
-        let mut distance = 0;
-        while let Some(value) = &chars.next() {
-            if value != &target {
-                distance += 1;
-            } else {
-                break;
+impl StringExt for str {
+    fn find_next_char_index(&self, start_index: usize, target: char) -> Option<usize> {
+        self.get(start_index..)?
+            .char_indices()
+            .find(|&(_, c)| c == target)
+            .map(|(i, _)| start_index + i)
+    }
+
+    fn find_next_delimiter_index(
+        &self,
+        start_index: usize,
+        delimiter_pair: (char, char),
+    ) -> Option<usize> {
+        let (open, close) = delimiter_pair;
+        let mut depth = 0usize;
+
+        for (i, c) in self.get(start_index..)?.char_indices() {
+            if depth > 0 && c == close {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(start_index + i);
+                }
+            } else if c == open {
+                depth += 1;
             }
         }
-        start_index + distance
+        None
+    }
+
+    fn find_matching_delimiter(
+        &self,
+        start_index: usize,
+        delimiters: &DelimiterSet,
+    ) -> Result<Option<usize>, DelimiterError> {
+        let mut opened = false;
+        let mut found = None;
+
+        delimiters.scan(self, start_index, |i, c, depth| {
+            if depth > 0 {
+                return ControlFlow::Continue(());
+            }
+            if opened && delimiters.is_closing(c) {
+                found = Some(i);
+                return ControlFlow::Break(());
+            }
+            opened |= delimiters.closing(c).is_some();
+            ControlFlow::Continue(())
+        })?;
+        Ok(found)
+    }
+
+    fn check_balanced(&self, delimiters: &DelimiterSet) -> Result<(), DelimiterError> {
+        delimiters.scan(self, 0, |_, _, _| ControlFlow::Continue(()))
     }
 
-    fn find_next_delimiter_index(&self, start_index: usize, delimiter_pair: (char, char)) -> usize {
-        let mut chars = self[start_index..self.len()].chars();
-        let mut delimiters = 0;
-        let mut distance = 0;
+    fn split_top_level(
+        &self,
+        sep: char,
+        delimiters: &DelimiterSet,
+    ) -> Result<Vec<&str>, DelimiterError> {
+        let mut pieces = vec![];
+        let mut piece_start = 0;
 
-        while let Some(value) = &chars.next() {
-            if value == &delimiter_pair.0 {
-                delimiters += 1;
+        delimiters.scan(self, 0, |i, c, depth| {
+            if depth == 0 && c == sep {
+                pieces.push(&self[piece_start..i]);
+                piece_start = i + c.len_utf8();
             }
-            if value == &delimiter_pair.1 {
-                delimiters -= 1;
+            ControlFlow::Continue(())
+        })?;
+        pieces.push(&self[piece_start..]);
+        Ok(pieces)
+    }
+
+    fn split_top_level_once(
+        &self,
+        sep: char,
+        delimiters: &DelimiterSet,
+    ) -> Result<Option<(&str, &str)>, DelimiterError> {
+        let mut found = None;
+
+        delimiters.scan(self, 0, |i, c, depth| {
+            if found.is_none() && depth == 0 && c == sep {
+                found = Some(i);
+            }
+            ControlFlow::Continue(())
+        })?;
+        Ok(found.map(|i| (&self[..i], &self[i + sep.len_utf8()..])))
+    }
+
+    fn to_snake_case(&self) -> String {
+        join_lowercase(self, "_")
+    }
+
+    fn to_kebab_case(&self) -> String {
+        join_lowercase(self, "-")
+    }
+
+    fn to_camel_case(&self) -> String {
+        let mut words = words(self).into_iter();
+        let first = words.next().map(str::to_lowercase).unwrap_or_default();
+        first + &words.map(capitalize).collect::<String>()
+    }
+
+    fn to_pascal_case(&self) -> String {
+        words(self).into_iter().map(capitalize).collect()
+    }
+
+    fn strip_margin(&self, margin: char) -> String {
+        self.split_inclusive('\n')
+            .map(|line| match line.trim_start().strip_prefix(margin) {
+                Some(stripped) => stripped,
+                None => line,
+            })
+            .collect()
+    }
+
+    fn dedent(&self) -> String {
+        let is_blank = |line: &str| line.trim().is_empty();
+        let indent = self
+            .lines()
+            .filter(|line| !is_blank(line))
+            .map(|line| &line[..line.len() - line.trim_start().len()])
+            .reduce(|common, indent| {
+                let shared: usize = common
+                    .chars()
+                    .zip(indent.chars())
+                    .take_while(|(a, b)| a == b)
+                    .map(|(a, _)| a.len_utf8())
+                    .sum();
+                &common[..shared]
+            })
+            .unwrap_or("");
+
+        self.split_inclusive('\n')
+            .map(|line| {
+                if is_blank(line) {
+                    &line[line.trim_end_matches(['\r', '\n']).len()..]
+                } else {
+                    &line[indent.len()..]
+                }
+            })
+            .collect()
+    }
+
+    fn truncate_graphemes(&self, max_graphemes: usize, ellipsis: &str) -> String {
+        if self.graphemes(true).nth(max_graphemes).is_none() {
+            return self.to_string();
+        }
+        let ellipsis_len = ellipsis.graphemes(true).count().min(max_graphemes);
+        self.graphemes(true)
+            .take(max_graphemes - ellipsis_len)
+            .chain(ellipsis.graphemes(true).take(ellipsis_len))
+            .collect()
+    }
+
+    fn line_col_at(&self, byte_offset: usize) -> Option<(usize, usize)> {
+        let before = self.get(..byte_offset)?;
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        let line = before.matches('\n').count() + 1;
+        Some((line, before[line_start..].chars().count() + 1))
+    }
+}
+//
+//      TESTS
+//
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Characters of every UTF-8 width, plus the delimiters the tests search for.
+    const ALPHABET: [char; 10] = ['a', '(', ')', 'é', 'ß', '€', '中', '😀', '𝄞', ' '];
+
+    /// Deterministic strings over `ALPHABET`, from a xorshift generator.
+    fn samples() -> impl Iterator<Item = String> {
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+        (0..2000).map(move |_| {
+            let len = next() % 24;
+            (0..len)
+                .map(|_| ALPHABET[(next() % ALPHABET.len() as u64) as usize])
+                .collect()
+        })
+    }
+
+    #[test]
+    fn find_next_char_index_matches_str_find() {
+        for s in samples() {
+            for start in 0..s.len() + 3 {
+                for target in ALPHABET {
+                    let expected = s
+                        .get(start..)
+                        .and_then(|rest| rest.find(target))
+                        .map(|i| start + i);
+                    assert_eq!(
+                        s.find_next_char_index(start, target),
+                        expected,
+                        "{s:?} {start}"
+                    );
+                }
             }
-            if delimiters == 0 {
-                break;
+        }
+    }
+
+    #[test]
+    fn find_next_delimiter_index_closes_first_open() {
+        for s in samples() {
+            for start in 0..s.len() + 3 {
+                let found = s.find_next_delimiter_index(start, ('(', ')'));
+                let Some(open) = s.find_next_char_index(start, '(') else {
+                    assert_eq!(found, None, "{s:?} {start}");
+                    continue;
+                };
+
+                let mut depth = 0;
+                let mut expected = None;
+                for (i, c) in s[open..].char_indices() {
+                    match c {
+                        '(' => depth += 1,
+                        ')' => depth -= 1,
+                        _ => continue,
+                    }
+                    if depth == 0 {
+                        expected = Some(open + i);
+                        break;
+                    }
+                }
+                assert_eq!(found, expected, "{s:?} {start}");
+                if let Some(close) = found {
+                    assert!(s[close..].starts_with(')'));
+                }
             }
-            distance += 1;
         }
-        start_index + distance
+    }
+
+    #[test]
+    fn find_inside_a_character_or_past_the_end() {
+        let s = "é(中)";
+        assert_eq!(s.find_next_char_index(0, '('), Some(2));
+        assert_eq!(s.find_next_char_index(1, '('), None);
+        assert_eq!(s.find_next_char_index(4, ')'), None);
+        assert_eq!(s.find_next_char_index(s.len(), ')'), None);
+        assert_eq!(s.find_next_char_index(s.len() + 1, ')'), None);
+
+        assert_eq!(s.find_next_delimiter_index(0, ('(', ')')), Some(6));
+        assert_eq!(s.find_next_delimiter_index(1, ('(', ')')), None);
+        assert_eq!(s.find_next_delimiter_index(3, ('(', ')')), None);
+        assert_eq!(s.find_next_delimiter_index(usize::MAX, ('(', ')')), None);
+    }
+
+    #[test]
+    fn find_skips_nested_non_ascii_delimiters() {
+        let s = "«a«b»c» d»";
+        assert_eq!(s.find_next_delimiter_index(0, ('«', '»')), Some(9));
+        assert_eq!(s.find_next_delimiter_index(3, ('«', '»')), Some(6));
+    }
+
+    #[test]
+    fn find_matching_delimiter_skips_quotes() {
+        let set = DelimiterSet::default();
+        let s = "(a \")\" b)";
+        assert_eq!(s.find_matching_delimiter(0, &set), Ok(Some(8)));
+        assert_eq!(
+            "x [a (b) {c}] y".find_matching_delimiter(0, &set),
+            Ok(Some(12))
+        );
+        assert_eq!("x [a (b)] y".find_matching_delimiter(3, &set), Ok(Some(7)));
+        assert_eq!("(\"\\\")\")".find_matching_delimiter(0, &set), Ok(Some(6)));
+        assert_eq!("no delimiters".find_matching_delimiter(0, &set), Ok(None));
+        assert_eq!("é(中)".find_matching_delimiter(1, &set), Ok(None));
+        assert_eq!("(a)".find_matching_delimiter(usize::MAX, &set), Ok(None));
+    }
+
+    #[test]
+    fn find_matching_delimiter_stops_before_later_errors() {
+        let set = DelimiterSet::default();
+        assert_eq!("(a) )".find_matching_delimiter(0, &set), Ok(Some(2)));
+        assert_eq!(
+            "(a ]".find_matching_delimiter(0, &set),
+            Err(DelimiterError::MismatchError {
+                open: '(',
+                open_position: 0,
+                found: ']',
+                position: 3
+            })
+        );
+        assert_eq!(
+            "(a".find_matching_delimiter(0, &set),
+            Err(DelimiterError::UnclosedError {
+                open: '(',
+                position: 0
+            })
+        );
+    }
+
+    #[test]
+    fn check_balanced_reports_the_first_error() {
+        let set = DelimiterSet::default();
+        assert_eq!("f(a[0], {b: \"}\"})".check_balanced(&set), Ok(()));
+        assert_eq!("".check_balanced(&set), Ok(()));
+        assert_eq!(
+            "a)".check_balanced(&set),
+            Err(DelimiterError::UnexpectedCloseError {
+                found: ')',
+                position: 1
+            })
+        );
+        assert_eq!(
+            "([)]".check_balanced(&set),
+            Err(DelimiterError::MismatchError {
+                open: '[',
+                open_position: 1,
+                found: ')',
+                position: 2
+            })
+        );
+        assert_eq!(
+            "(é [".check_balanced(&set),
+            Err(DelimiterError::UnclosedError {
+                open: '[',
+                position: 4
+            })
+        );
+        assert_eq!(
+            "(\"a)".check_balanced(&set),
+            Err(DelimiterError::UnterminatedQuoteError {
+                quote: '"',
+                position: 1
+            })
+        );
+    }
+
+    #[test]
+    fn default_quotes_ignore_apostrophes() {
+        let set = DelimiterSet::default();
+        assert_eq!("don't (x)".check_balanced(&set), Ok(()));
+        assert_eq!("don't (x)".find_matching_delimiter(0, &set), Ok(Some(8)));
+
+        let set = set.with_quotes(&['"', '\'']);
+        assert_eq!("'(' (x)".find_matching_delimiter(0, &set), Ok(Some(6)));
+        assert_eq!(
+            "don't (x)".check_balanced(&set),
+            Err(DelimiterError::UnterminatedQuoteError {
+                quote: '\'',
+                position: 3
+            })
+        );
+    }
+
+    #[test]
+    fn escapes_only_apply_inside_quotes() {
+        let set = DelimiterSet::default();
+        assert_eq!("\\(a)".find_matching_delimiter(0, &set), Ok(Some(3)));
+        assert_eq!("\"a\\\\\"".check_balanced(&set), Ok(()));
+        assert_eq!(
+            "\"a\\\"".check_balanced(&set),
+            Err(DelimiterError::UnterminatedQuoteError {
+                quote: '"',
+                position: 0
+            })
+        );
+
+        let unescaped = DelimiterSet::new(&[('(', ')')]).with_quotes(&['"']);
+        assert_eq!("(\"\\\")".check_balanced(&unescaped), Ok(()));
+        assert!("(\"\\\")".check_balanced(&set).is_err());
+    }
+
+    #[test]
+    fn split_top_level_skips_nested_separators() {
+        let set = DelimiterSet::default();
+        assert_eq!(
+            "a, f(b, c), [d, {e, f}], \"g, h\"".split_top_level(',', &set),
+            Ok(vec!["a", " f(b, c)", " [d, {e, f}]", " \"g, h\""])
+        );
+        assert_eq!(
+            "a,,b,".split_top_level(',', &set),
+            Ok(vec!["a", "", "b", ""])
+        );
+        assert_eq!("".split_top_level(',', &set), Ok(vec![""]));
+        assert_eq!("(a, b)".split_top_level(',', &set), Ok(vec!["(a, b)"]));
+    }
+
+    #[test]
+    fn split_top_level_handles_multi_byte_separators() {
+        let set = DelimiterSet::default();
+        assert_eq!(
+            "é→(a→b)→中".split_top_level('→', &set),
+            Ok(vec!["é", "(a→b)", "中"])
+        );
+        assert_eq!(
+            "😀(😀)😀".split_top_level('😀', &set),
+            Ok(vec!["", "(😀)", ""])
+        );
+        assert_eq!(
+            "a→(b→c)→d".split_top_level_once('→', &set),
+            Ok(Some(("a", "(b→c)→d")))
+        );
+    }
+
+    #[test]
+    fn split_top_level_once_finds_the_first_separator() {
+        let set = DelimiterSet::default();
+        assert_eq!(
+            "\"k=v\"=f(a=b)=c".split_top_level_once('=', &set),
+            Ok(Some(("\"k=v\"", "f(a=b)=c")))
+        );
+        assert_eq!("=".split_top_level_once('=', &set), Ok(Some(("", ""))));
+        assert_eq!("(a=b)".split_top_level_once('=', &set), Ok(None));
+        assert_eq!("".split_top_level_once('=', &set), Ok(None));
+    }
+
+    #[test]
+    fn split_top_level_rejects_unbalanced_input() {
+        let set = DelimiterSet::default();
+        let unclosed = Err(DelimiterError::UnclosedError {
+            open: '(',
+            position: 2,
+        });
+        assert_eq!("a,(b,c".split_top_level(',', &set), unclosed);
+        // the separator comes before the error, but the whole string is still checked
+        assert_eq!(
+            "a,(b,c".split_top_level_once(',', &set),
+            unclosed.map(|_| None)
+        );
+        assert_eq!(
+            "a,b)".split_top_level(',', &set),
+            Err(DelimiterError::UnexpectedCloseError {
+                found: ')',
+                position: 3
+            })
+        );
+        assert_eq!(
+            "a,\"b".split_top_level_once(',', &set),
+            Err(DelimiterError::UnterminatedQuoteError {
+                quote: '"',
+                position: 2
+            })
+        );
+    }
+
+    #[test]
+    fn case_conversions_split_acronyms() {
+        assert_eq!("HTTPServer".to_snake_case(), "http_server");
+        assert_eq!("HTTPServer".to_kebab_case(), "http-server");
+        assert_eq!("HTTPServer".to_camel_case(), "httpServer");
+        assert_eq!("parseHTTPRequest".to_pascal_case(), "ParseHttpRequest");
+        assert_eq!("IO".to_snake_case(), "io");
+    }
+
+    #[test]
+    fn case_conversions_keep_digits_with_the_word_before() {
+        assert_eq!("vec3Normal".to_snake_case(), "vec3_normal");
+        assert_eq!("vec3Normal".to_pascal_case(), "Vec3Normal");
+        assert_eq!("vec3_normal".to_camel_case(), "vec3Normal");
+        assert_eq!("utf8-to-utf16".to_camel_case(), "utf8ToUtf16");
+    }
+
+    #[test]
+    fn case_conversions_handle_non_ascii_letters() {
+        assert_eq!("ÉcoleNormale".to_snake_case(), "école_normale");
+        assert_eq!("über_größe".to_camel_case(), "überGröße");
+        assert_eq!("über größe".to_pascal_case(), "ÜberGröße");
+        assert_eq!("ΑλφαΒήτα".to_kebab_case(), "αλφα-βήτα");
+        assert_eq!("  --  ".to_snake_case(), "");
+    }
+
+    #[test]
+    fn strip_margin_only_touches_margined_lines() {
+        let text = "  |first\n  | second\nplain\n\t|last";
+        assert_eq!(text.strip_margin('|'), "first\n second\nplain\nlast");
+    }
+
+    #[test]
+    fn dedent_keeps_crlf_line_endings() {
+        assert_eq!("    a\r\n      b\r\n".dedent(), "a\r\n  b\r\n");
+        assert_eq!("  a\r\n    \r\n  b".dedent(), "a\r\n\r\nb");
+        assert_eq!("\ta\n\t\tb".dedent(), "a\n\tb");
+        assert_eq!("  a\n\tb".dedent(), "  a\n\tb");
+    }
+
+    #[test]
+    fn truncate_graphemes_counts_clusters() {
+        assert_eq!("hello".truncate_graphemes(5, "…"), "hello");
+        assert_eq!("hello world".truncate_graphemes(6, "…"), "hello…");
+        // `e` and a combining acute accent are one grapheme
+        assert_eq!("cafe\u{301}s".truncate_graphemes(4, ""), "cafe\u{301}");
+        assert_eq!("👨‍👩‍👧abc".truncate_graphemes(2, "…"), "👨‍👩‍👧…");
+    }
+
+    #[test]
+    fn truncate_graphemes_cuts_a_long_ellipsis() {
+        assert_eq!("hello world".truncate_graphemes(2, "..."), "..");
+        assert_eq!("hello world".truncate_graphemes(0, "..."), "");
+        assert_eq!("hi".truncate_graphemes(1, "..."), ".");
+        assert_eq!("hi".truncate_graphemes(2, "..."), "hi");
+    }
+
+    #[test]
+    fn line_col_at_counts_characters() {
+        let text = "añb\n中x\n";
+        assert_eq!(text.line_col_at(0), Some((1, 1)));
+        assert_eq!(text.line_col_at(1), Some((1, 2)));
+        assert_eq!(text.line_col_at(2), None);
+        assert_eq!(text.line_col_at(3), Some((1, 3)));
+        assert_eq!(text.line_col_at(5), Some((2, 1)));
+        assert_eq!(text.line_col_at(6), None);
+        assert_eq!(text.line_col_at(8), Some((2, 2)));
+        assert_eq!(text.line_col_at(text.len()), Some((3, 1)));
+        assert_eq!(text.line_col_at(text.len() + 1), None);
     }
 }
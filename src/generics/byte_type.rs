@@ -2,14 +2,214 @@ use bytemuck::NoUninit;
 use bytemuck::Pod;
 use bytemuck::cast_slice;
 use std::any::TypeId;
+use std::mem;
 
-use crate::generics::{NumericType, VectorType};
+use thiserror::Error;
 
+use crate::generics::NumericType;
+//
+//      ERRORS
+//
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ByteError {
+    #[error("Expected {expected} bytes, found {found}.")]
+    LengthError { expected: usize, found: usize },
+    #[error("{found} bytes do not divide into {element_size} byte elements.")]
+    AlignmentError { element_size: usize, found: usize },
+    #[error("Unknown enum discriminant {0}.")]
+    DiscriminantError(i128),
+    #[error("Varint does not fit in {0}.")]
+    OverflowError(&'static str),
+    #[error("Invalid UTF-8 string.")]
+    Utf8Error,
+    #[error("Unsupported format version {0}.")]
+    VersionError(u8),
+    #[error("Malformed input at byte {0}.")]
+    MalformedError(usize),
+}
+//
+//      ENUMS
+//
+/// Byte order used by `copy_bytes_endian`/`read_bytes_endian`. `Native` is the host order,
+/// which is what plain `to_bytes`/`from_bytes` use.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Endian {
+    Little,
+    Big,
+    Native,
+}
+
+impl Endian {
+    pub fn is_native(self) -> bool {
+        match self {
+            Endian::Native => true,
+            Endian::Little => cfg!(target_endian = "little"),
+            Endian::Big => cfg!(target_endian = "big"),
+        }
+    }
+}
+//
+//      TRAITS
+//
 pub trait Byteable {
     fn to_bytes(self) -> Vec<u8>;
     fn copy_bytes(&self) -> Vec<u8>;
+    /// Types without a meaningful byte order ignore `endian`.
+    fn copy_bytes_endian(&self, endian: Endian) -> Vec<u8> {
+        self.copy_bytes()
+    }
+    fn to_bytes_le(&self) -> Vec<u8> {
+        self.copy_bytes_endian(Endian::Little)
+    }
+    fn to_bytes_be(&self) -> Vec<u8> {
+        self.copy_bytes_endian(Endian::Big)
+    }
+}
+
+/// The reverse of `Byteable`. Input slices need no particular alignment.
+pub trait FromBytes: Sized {
+    /// Decodes a value from the front of `bytes`, returning it with the unread remainder.
+    fn read_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), ByteError>;
+
+    fn read_bytes_endian(bytes: &[u8], endian: Endian) -> Result<(Self, &[u8]), ByteError> {
+        Self::read_bytes(bytes)
+    }
+
+    /// Decodes a value that must span all of `bytes`.
+    fn from_bytes(bytes: &[u8]) -> Result<Self, ByteError> {
+        Self::from_bytes_endian(bytes, Endian::Native)
+    }
+
+    fn from_bytes_endian(bytes: &[u8], endian: Endian) -> Result<Self, ByteError> {
+        let (value, rest) = Self::read_bytes_endian(bytes, endian)?;
+        check_len(bytes, bytes.len() - rest.len())?;
+        Ok(value)
+    }
+    fn from_bytes_le(bytes: &[u8]) -> Result<Self, ByteError> {
+        Self::from_bytes_endian(bytes, Endian::Little)
+    }
+    fn from_bytes_be(bytes: &[u8]) -> Result<Self, ByteError> {
+        Self::from_bytes_endian(bytes, Endian::Big)
+    }
+}
+//
+//      STRUCTS
+//
+/// Encodes an integer as LEB128: unsigned types as plain LEB128, signed types as signed
+/// LEB128. The encoding is independent of byte order, so `endian` is ignored.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Varint<T>(pub T);
+//
+//      HELPERS
+//
+fn check_len(bytes: &[u8], expected: usize) -> Result<(), ByteError> {
+    if bytes.len() != expected {
+        return Err(ByteError::LengthError {
+            expected,
+            found: bytes.len(),
+        });
+    }
+    Ok(())
+}
+
+fn write_uleb128(mut value: u128, out: &mut Vec<u8>) {
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn write_sleb128(mut value: i128, out: &mut Vec<u8>) {
+    loop {
+        let byte = (value as u8) & 0x7f;
+        value >>= 7;
+        let done = (value == 0 && byte & 0x40 == 0) || (value == -1 && byte & 0x40 != 0);
+        if done {
+            out.push(byte);
+            return;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+/// Reads the 7-bit groups of a LEB128 value, returning them with the shift of the last one.
+/// Bits of a group past the 128th must be zero, or for `signed` values copies of the 128th.
+fn read_leb128<'a>(
+    bytes: &'a [u8],
+    type_name: &'static str,
+    signed: bool,
+) -> Result<(u128, u32, &'a [u8]), ByteError> {
+    let mut value: u128 = 0;
+    let mut shift = 0;
+
+    for (i, byte) in bytes.iter().enumerate() {
+        let bits = (byte & 0x7f) as u128;
+        if shift >= u128::BITS {
+            return Err(ByteError::OverflowError(type_name));
+        }
+        let kept = u128::BITS - shift;
+        if kept < 7 {
+            let negative = signed && bits & (1 << (kept - 1)) != 0;
+            let expected = if negative { 0x7f >> kept } else { 0 };
+            if bits >> kept != expected {
+                return Err(ByteError::OverflowError(type_name));
+            }
+        }
+        value |= bits << shift;
+
+        if byte & 0x80 == 0 {
+            return Ok((value, shift, &bytes[i + 1..]));
+        }
+        shift += 7;
+    }
+
+    Err(ByteError::LengthError {
+        expected: bytes.len() + 1,
+        found: bytes.len(),
+    })
+}
+
+fn read_uleb128<'a>(
+    bytes: &'a [u8],
+    type_name: &'static str,
+) -> Result<(u128, &'a [u8]), ByteError> {
+    let (value, _, rest) = read_leb128(bytes, type_name, false)?;
+    Ok((value, rest))
+}
+
+fn read_sleb128<'a>(
+    bytes: &'a [u8],
+    type_name: &'static str,
+) -> Result<(i128, &'a [u8]), ByteError> {
+    let (value, shift, rest) = read_leb128(bytes, type_name, true)?;
+    let used = shift + 7;
+    let value = if used < i128::BITS && value & (1 << (used - 1)) != 0 {
+        (value | (u128::MAX << used)) as i128
+    } else {
+        value as i128
+    };
+    Ok((value, rest))
+}
+
+fn read_len(bytes: &[u8]) -> Result<(usize, &[u8]), ByteError> {
+    let (len, rest) = read_uleb128(bytes, "usize")?;
+    let len = usize::try_from(len).map_err(|_| ByteError::OverflowError("usize"))?;
+    Ok((len, rest))
 }
 
+fn split_prefix(bytes: &[u8], len: usize) -> Result<(&[u8], &[u8]), ByteError> {
+    if bytes.len() < len {
+        return Err(ByteError::LengthError {
+            expected: len,
+            found: bytes.len(),
+        });
+    }
+    Ok(bytes.split_at(len))
+}
+//
+//      BYTEABLE IMPLS
+//
 impl<T: NumericType> Byteable for T {
     fn to_bytes(self) -> Vec<u8> {
         bytemuck::cast_slice(&[self]).into()
@@ -17,8 +217,16 @@ impl<T: NumericType> Byteable for T {
     fn copy_bytes(&self) -> Vec<u8> {
         self.to_owned().to_bytes()
     }
+    fn copy_bytes_endian(&self, endian: Endian) -> Vec<u8> {
+        let mut bytes = self.copy_bytes();
+        if !endian.is_native() {
+            bytes.reverse();
+        }
+        bytes
+    }
 }
 
+/// Raw host-order bytes with no length prefix, e.g. for GPU buffers.
 impl<T: Pod> Byteable for &[T] {
     fn to_bytes(self) -> Vec<u8> {
         bytemuck::cast_slice(self).into()
@@ -28,12 +236,515 @@ impl<T: Pod> Byteable for &[T] {
     }
 }
 
-impl<T: Pod> Byteable for Vec<T> {
+impl<T: Pod> Byteable for Box<[T]> {
+    fn to_bytes(self) -> Vec<u8> {
+        self.copy_bytes()
+    }
+    fn copy_bytes(&self) -> Vec<u8> {
+        bytemuck::cast_slice(self).into()
+    }
+}
+
+/// A varint element count followed by the elements.
+impl<T: Byteable> Byteable for Vec<T> {
     fn to_bytes(self) -> Vec<u8> {
-        bytemuck::cast_slice(&self).into()
+        self.copy_bytes()
+    }
+    fn copy_bytes(&self) -> Vec<u8> {
+        self.copy_bytes_endian(Endian::Native)
+    }
+    fn copy_bytes_endian(&self, endian: Endian) -> Vec<u8> {
+        let mut bytes = Varint(self.len()).copy_bytes();
+        for x in self {
+            bytes.extend(x.copy_bytes_endian(endian));
+        }
+        bytes
+    }
+}
+
+/// A varint byte length followed by the UTF-8 bytes.
+impl Byteable for String {
+    fn to_bytes(self) -> Vec<u8> {
+        self.copy_bytes()
+    }
+    fn copy_bytes(&self) -> Vec<u8> {
+        let mut bytes = Varint(self.len()).copy_bytes();
+        bytes.extend(self.as_bytes());
+        bytes
+    }
+}
+
+impl Byteable for &str {
+    fn to_bytes(self) -> Vec<u8> {
+        self.copy_bytes()
+    }
+    fn copy_bytes(&self) -> Vec<u8> {
+        let mut bytes = Varint(self.len()).copy_bytes();
+        bytes.extend(self.as_bytes());
+        bytes
+    }
+}
+
+/// A `0` tag byte for `None`, or a `1` followed by the value.
+impl<T: Byteable> Byteable for Option<T> {
+    fn to_bytes(self) -> Vec<u8> {
+        self.copy_bytes()
+    }
+    fn copy_bytes(&self) -> Vec<u8> {
+        self.copy_bytes_endian(Endian::Native)
+    }
+    fn copy_bytes_endian(&self, endian: Endian) -> Vec<u8> {
+        match self {
+            Some(value) => {
+                let mut bytes = vec![1];
+                bytes.extend(value.copy_bytes_endian(endian));
+                bytes
+            }
+            None => vec![0],
+        }
     }
+}
+
+macro_rules! impl_varint {
+    ($($T:ty: $write:ident, $read:ident, $wide:ty);+ $(;)?) => {
+        $(
+            impl Byteable for Varint<$T> {
+                fn to_bytes(self) -> Vec<u8> {
+                    self.copy_bytes()
+                }
+                fn copy_bytes(&self) -> Vec<u8> {
+                    let mut bytes = vec![];
+                    $write(self.0 as $wide, &mut bytes);
+                    bytes
+                }
+            }
 
+            impl FromBytes for Varint<$T> {
+                fn read_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), ByteError> {
+                    let (value, rest) = $read(bytes, stringify!($T))?;
+                    let value = <$T>::try_from(value)
+                        .map_err(|_| ByteError::OverflowError(stringify!($T)))?;
+                    Ok((Varint(value), rest))
+                }
+            }
+        )+
+    };
+}
+
+impl_varint!(
+    u8: write_uleb128, read_uleb128, u128;
+    u16: write_uleb128, read_uleb128, u128;
+    u32: write_uleb128, read_uleb128, u128;
+    u64: write_uleb128, read_uleb128, u128;
+    u128: write_uleb128, read_uleb128, u128;
+    usize: write_uleb128, read_uleb128, u128;
+    i8: write_sleb128, read_sleb128, i128;
+    i16: write_sleb128, read_sleb128, i128;
+    i32: write_sleb128, read_sleb128, i128;
+    i64: write_sleb128, read_sleb128, i128;
+    i128: write_sleb128, read_sleb128, i128;
+    isize: write_sleb128, read_sleb128, i128;
+);
+/// Arrays and tuples have their length in the type, so they are written without a prefix.
+impl<T: Byteable, const N: usize> Byteable for [T; N] {
+    fn to_bytes(self) -> Vec<u8> {
+        self.copy_bytes()
+    }
     fn copy_bytes(&self) -> Vec<u8> {
-        self.clone().to_bytes()
+        self.copy_bytes_endian(Endian::Native)
+    }
+    fn copy_bytes_endian(&self, endian: Endian) -> Vec<u8> {
+        self.iter()
+            .flat_map(|x| x.copy_bytes_endian(endian))
+            .collect()
+    }
+}
+
+macro_rules! impl_tuple_byteable {
+    ($($T:ident: $idx:tt),+) => {
+        impl<$($T: Byteable),+> Byteable for ($($T,)+) {
+            fn to_bytes(self) -> Vec<u8> {
+                self.copy_bytes()
+            }
+            fn copy_bytes(&self) -> Vec<u8> {
+                self.copy_bytes_endian(Endian::Native)
+            }
+            fn copy_bytes_endian(&self, endian: Endian) -> Vec<u8> {
+                let mut bytes = vec![];
+                $(bytes.extend(self.$idx.copy_bytes_endian(endian));)+
+                bytes
+            }
+        }
+    };
+}
+
+impl_tuple_byteable!(A: 0);
+impl_tuple_byteable!(A: 0, B: 1);
+impl_tuple_byteable!(A: 0, B: 1, C: 2);
+impl_tuple_byteable!(A: 0, B: 1, C: 2, D: 3);
+impl_tuple_byteable!(A: 0, B: 1, C: 2, D: 3, E: 4);
+impl_tuple_byteable!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5);
+impl_tuple_byteable!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6);
+impl_tuple_byteable!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6, H: 7);
+//
+//      FROMBYTES IMPLS
+//
+impl<T: NumericType> FromBytes for T {
+    fn read_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), ByteError> {
+        let (value, rest) = split_prefix(bytes, mem::size_of::<T>())?;
+        Ok((bytemuck::pod_read_unaligned(value), rest))
+    }
+
+    fn read_bytes_endian(bytes: &[u8], endian: Endian) -> Result<(Self, &[u8]), ByteError> {
+        let (value, rest) = split_prefix(bytes, mem::size_of::<T>())?;
+        if endian.is_native() {
+            return Ok((bytemuck::pod_read_unaligned(value), rest));
+        }
+        let mut swapped = value.to_vec();
+        swapped.reverse();
+        Ok((bytemuck::pod_read_unaligned(&swapped), rest))
+    }
+}
+
+impl<T: FromBytes> FromBytes for Vec<T> {
+    fn read_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), ByteError> {
+        Self::read_bytes_endian(bytes, Endian::Native)
+    }
+
+    fn read_bytes_endian(bytes: &[u8], endian: Endian) -> Result<(Self, &[u8]), ByteError> {
+        let (len, mut rest) = read_len(bytes)?;
+        // every element takes at least a byte unless it is zero sized
+        let mut values = Vec::with_capacity(len.min(rest.len()));
+        for _ in 0..len {
+            let (value, r) = T::read_bytes_endian(rest, endian)?;
+            values.push(value);
+            rest = r;
+        }
+        Ok((values, rest))
+    }
+}
+
+/// Raw host-order elements, the owned counterpart of `&[T]`. Consumes all remaining input.
+impl<T: Pod> FromBytes for Box<[T]> {
+    fn read_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), ByteError> {
+        let element_size = mem::size_of::<T>();
+        if element_size == 0 || !bytes.len().is_multiple_of(element_size) {
+            return Err(ByteError::AlignmentError {
+                element_size,
+                found: bytes.len(),
+            });
+        }
+        let values: Vec<T> = bytemuck::pod_collect_to_vec(bytes);
+        Ok((values.into_boxed_slice(), &bytes[bytes.len()..]))
+    }
+}
+
+impl<T: FromBytes> FromBytes for Option<T> {
+    fn read_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), ByteError> {
+        Self::read_bytes_endian(bytes, Endian::Native)
+    }
+
+    fn read_bytes_endian(bytes: &[u8], endian: Endian) -> Result<(Self, &[u8]), ByteError> {
+        let (tag, rest) = split_prefix(bytes, 1)?;
+        match tag[0] {
+            0 => Ok((None, rest)),
+            1 => {
+                let (value, rest) = T::read_bytes_endian(rest, endian)?;
+                Ok((Some(value), rest))
+            }
+            tag => Err(ByteError::DiscriminantError(tag as i128)),
+        }
+    }
+}
+
+impl FromBytes for String {
+    fn read_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), ByteError> {
+        let (len, rest) = read_len(bytes)?;
+        let (value, rest) = split_prefix(rest, len)?;
+        let value = String::from_utf8(value.to_vec()).map_err(|_| ByteError::Utf8Error)?;
+        Ok((value, rest))
+    }
+}
+
+impl<T: FromBytes, const N: usize> FromBytes for [T; N] {
+    fn read_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), ByteError> {
+        Self::read_bytes_endian(bytes, Endian::Native)
+    }
+
+    fn read_bytes_endian(bytes: &[u8], endian: Endian) -> Result<(Self, &[u8]), ByteError> {
+        let mut rest = bytes;
+        let mut values = Vec::with_capacity(N);
+        for _ in 0..N {
+            let (value, r) = T::read_bytes_endian(rest, endian)?;
+            values.push(value);
+            rest = r;
+        }
+        let Ok(array) = values.try_into() else {
+            unreachable!()
+        };
+        Ok((array, rest))
+    }
+}
+
+macro_rules! impl_tuple_from_bytes {
+    ($($T:ident),+) => {
+        impl<$($T: FromBytes),+> FromBytes for ($($T,)+) {
+            fn read_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), ByteError> {
+                Self::read_bytes_endian(bytes, Endian::Native)
+            }
+
+            #[allow(non_snake_case)]
+            fn read_bytes_endian(
+                bytes: &[u8],
+                endian: Endian,
+            ) -> Result<(Self, &[u8]), ByteError> {
+                let rest = bytes;
+                $(let ($T, rest) = $T::read_bytes_endian(rest, endian)?;)+
+                Ok((($($T,)+), rest))
+            }
+        }
+    };
+}
+
+impl_tuple_from_bytes!(A);
+impl_tuple_from_bytes!(A, B);
+impl_tuple_from_bytes!(A, B, C);
+impl_tuple_from_bytes!(A, B, C, D);
+impl_tuple_from_bytes!(A, B, C, D, E);
+impl_tuple_from_bytes!(A, B, C, D, E, F);
+impl_tuple_from_bytes!(A, B, C, D, E, F, G);
+impl_tuple_from_bytes!(A, B, C, D, E, F, G, H);
+//
+//      TESTS
+//
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip<T: Copy + std::fmt::Debug + PartialEq>(value: T)
+    where
+        Varint<T>: Byteable + FromBytes,
+    {
+        let bytes = Varint(value).copy_bytes();
+        assert_eq!(
+            Varint::<T>::from_bytes(&bytes),
+            Ok(Varint(value)),
+            "{value:?}"
+        );
+    }
+
+    #[test]
+    fn varint_round_trips_bounds() {
+        macro_rules! check {
+            ($($T:ty),+) => {
+                $(
+                    round_trip(<$T>::MIN);
+                    round_trip(<$T>::MAX);
+                    round_trip(0 as $T);
+                )+
+            };
+        }
+        check!(
+            u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize
+        );
+    }
+
+    #[test]
+    fn varint_round_trips_wide_negative_i128() {
+        for value in [i128::MIN, i128::MIN + 1, -(1 << 126) - 1, -(1 << 126), -1] {
+            round_trip(value);
+        }
+    }
+
+    #[test]
+    fn varint_rejects_bits_past_128() {
+        let mut unsigned = vec![0xff; 18];
+        unsigned.push(0x04);
+        assert_eq!(
+            Varint::<u128>::from_bytes(&unsigned),
+            Err(ByteError::OverflowError("u128"))
+        );
+
+        // the last group's sign bit is set but the bits above it aren't
+        let mut signed = vec![0x80; 18];
+        signed.push(0x3e);
+        assert_eq!(
+            Varint::<i128>::from_bytes(&signed),
+            Err(ByteError::OverflowError("i128"))
+        );
+    }
+
+    #[cfg(feature = "derive")]
+    mod derive {
+        use crate::generics::{ByteError, Byteable, Endian, FromBytes};
+
+        #[derive(Byteable, FromBytes, Debug, PartialEq)]
+        struct Named {
+            a: u8,
+            b: u16,
+            name: String,
+        }
+
+        #[derive(Byteable, FromBytes, Debug, PartialEq)]
+        struct Tuple(u8, i32);
+
+        #[derive(Byteable, FromBytes, Debug, PartialEq)]
+        struct Generic<T> {
+            value: T,
+            tag: u8,
+        }
+
+        #[derive(Byteable, FromBytes, Debug, PartialEq)]
+        #[repr(u8)]
+        enum Explicit {
+            A = 3,
+            B(u16) = 7,
+            C { x: u8 },
+        }
+
+        #[derive(Byteable, FromBytes, Debug, PartialEq)]
+        enum Signed {
+            Negative = -2,
+            Next,
+        }
+
+        #[derive(Byteable, FromBytes, Debug, PartialEq)]
+        #[byteable(endian = "big")]
+        struct Big(u16, u32);
+
+        #[derive(Byteable, FromBytes, Debug, PartialEq, Clone, Copy)]
+        #[byteable(padding = "c")]
+        #[repr(C)]
+        struct CLayout {
+            a: u8,
+            b: u32,
+            c: u16,
+        }
+
+        #[derive(Byteable, FromBytes, Debug, PartialEq)]
+        #[byteable(padding = "c")]
+        #[repr(u16)]
+        enum CEnum {
+            A(u8, u32),
+        }
+
+        fn round_trip<T: Byteable + FromBytes + std::fmt::Debug + PartialEq>(value: T) {
+            for endian in [Endian::Little, Endian::Big, Endian::Native] {
+                let bytes = value.copy_bytes_endian(endian);
+                assert_eq!(
+                    T::from_bytes_endian(&bytes, endian).as_ref(),
+                    Ok(&value),
+                    "{endian:?}"
+                );
+            }
+        }
+
+        #[test]
+        fn structs_write_fields_in_order() {
+            let named = Named {
+                a: 1,
+                b: 0x0203,
+                name: "hi".into(),
+            };
+            assert_eq!(
+                named.copy_bytes_endian(Endian::Big),
+                [
+                    &[1, 2, 3][..],
+                    &"hi".to_string().copy_bytes_endian(Endian::Big)
+                ]
+                .concat()
+            );
+            round_trip(named);
+
+            assert_eq!(
+                Tuple(1, -1).copy_bytes_endian(Endian::Little),
+                [1, 0xff, 0xff, 0xff, 0xff]
+            );
+            round_trip(Tuple(9, i32::MIN));
+        }
+
+        #[test]
+        fn generics_bound_their_fields() {
+            round_trip(Generic {
+                value: 5u64,
+                tag: 1,
+            });
+            round_trip(Generic {
+                value: String::from("text"),
+                tag: 2,
+            });
+            round_trip(Generic {
+                value: Tuple(1, 2),
+                tag: 3,
+            });
+        }
+
+        #[test]
+        fn enums_write_their_discriminant() {
+            assert_eq!(Explicit::A.copy_bytes_endian(Endian::Big), [3]);
+            assert_eq!(
+                Explicit::B(0x0102).copy_bytes_endian(Endian::Big),
+                [7, 1, 2]
+            );
+            assert_eq!(Explicit::C { x: 4 }.copy_bytes_endian(Endian::Big), [8, 4]);
+            round_trip(Explicit::A);
+            round_trip(Explicit::B(500));
+            round_trip(Explicit::C { x: 4 });
+
+            assert_eq!(
+                Explicit::from_bytes(&[4]),
+                Err(ByteError::DiscriminantError(4))
+            );
+        }
+
+        #[test]
+        fn enums_without_repr_use_isize() {
+            assert_eq!(
+                Signed::Negative.copy_bytes_endian(Endian::Little),
+                (-2isize).to_le_bytes()
+            );
+            assert_eq!(
+                Signed::Next.copy_bytes_endian(Endian::Little),
+                (-1isize).to_le_bytes()
+            );
+            round_trip(Signed::Negative);
+            round_trip(Signed::Next);
+        }
+
+        #[test]
+        fn fixed_endian_overrides_the_request() {
+            let bytes = [0, 1, 0, 0, 0, 2];
+            assert_eq!(Big(1, 2).copy_bytes_endian(Endian::Little), bytes);
+            assert_eq!(Big(1, 2).copy_bytes(), bytes);
+            assert_eq!(
+                Big::from_bytes_endian(&bytes, Endian::Little),
+                Ok(Big(1, 2))
+            );
+        }
+
+        #[test]
+        fn c_padding_matches_repr_c() {
+            let value = CLayout { a: 1, b: 2, c: 3 };
+            let bytes = value.copy_bytes_endian(Endian::Native);
+            assert_eq!(bytes.len(), size_of::<CLayout>());
+            assert_eq!(bytes[0], 1);
+            assert_eq!(&bytes[1..4], [0, 0, 0]);
+            assert_eq!(bytes[4..8], 2u32.to_ne_bytes());
+            assert_eq!(bytes[8..10], 3u16.to_ne_bytes());
+            assert_eq!(&bytes[10..], [0, 0]);
+            round_trip(value);
+
+            let bytes = CEnum::A(1, 2).copy_bytes_endian(Endian::Little);
+            assert_eq!(bytes, [0, 0, 1, 0, 2, 0, 0, 0]);
+            round_trip(CEnum::A(1, 2));
+
+            assert_eq!(
+                CLayout::from_bytes(&bytes[..1]),
+                Err(ByteError::LengthError {
+                    expected: 3,
+                    found: 0
+                })
+            );
+        }
     }
 }
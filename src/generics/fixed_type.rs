@@ -0,0 +1,210 @@
+use std::{fmt::Display, num::ParseFloatError, str::FromStr};
+
+use bytemuck::{Pod, Zeroable};
+
+use crate::generics::{IntegerType, NumericType};
+
+/// A fixed-point number stored as `bits / 2^FRAC`.
+///
+/// Products and quotients are computed in 128 bits and truncated toward zero, which is exact
+/// for any `I` up to 64 bits wide. Conversions from floats round to nearest, ties away from
+/// zero, and saturate at the bounds. Conversions to `f64` are exact while `bits` fits in 53
+/// bits.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Fixed<I, const FRAC: u32> {
+    bits: I,
+}
+
+// SAFETY: `Fixed` is a transparent wrapper around `I`.
+unsafe impl<I: Zeroable, const FRAC: u32> Zeroable for Fixed<I, FRAC> {}
+unsafe impl<I: Pod, const FRAC: u32> Pod for Fixed<I, FRAC> {}
+
+impl<I: IntegerType, const FRAC: u32> Fixed<I, FRAC> {
+    const VALID_FRAC: () = assert!(FRAC < I::BITS && FRAC < 127, "FRAC is too large for I");
+    //
+    //      PRIVATE
+    //
+    fn scale() -> i128 {
+        1 << FRAC
+    }
+
+    fn wide_mul(self, rhs: Self) -> Option<i128> {
+        let product = self.bits.to_i128().checked_mul(rhs.bits.to_i128())?;
+        Some(product / Self::scale())
+    }
+
+    fn wide_div(self, rhs: Self) -> Option<i128> {
+        let numerator = self.bits.to_i128().checked_mul(Self::scale())?;
+        numerator.checked_div(rhs.bits.to_i128())
+    }
+
+    fn from_wide_saturating(value: i128) -> Self {
+        let clamped = value.clamp(I::min_value().to_i128(), I::max_value().to_i128());
+        Self::from_bits(I::wrapping_from_i128(clamped))
+    }
+
+    fn saturated(self, rhs: Self) -> Self {
+        if (self.bits < I::zero()) != (rhs.bits < I::zero()) {
+            Self::min_value()
+        } else {
+            Self::max_value()
+        }
+    }
+    //
+    //      CONSTRUCTORS
+    //
+    pub fn from_bits(bits: I) -> Self {
+        let () = Self::VALID_FRAC;
+        Self { bits }
+    }
+
+    pub fn from_int(value: I) -> Self {
+        Self::from_bits(value << FRAC)
+    }
+
+    pub fn to_bits(self) -> I {
+        self.bits
+    }
+}
+//
+//      OPERATOR IMPLS
+//
+impl<I: IntegerType, const FRAC: u32> std::ops::Add for Fixed<I, FRAC> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self::from_bits(self.bits + rhs.bits)
+    }
+}
+
+impl<I: IntegerType, const FRAC: u32> std::ops::Sub for Fixed<I, FRAC> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self::from_bits(self.bits - rhs.bits)
+    }
+}
+
+/// Panics on overflow; use the wrapping or saturating variants otherwise.
+impl<I: IntegerType, const FRAC: u32> std::ops::Mul for Fixed<I, FRAC> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        self.checked_mul(rhs)
+            .expect("fixed-point multiplication overflowed")
+    }
+}
+
+/// Panics on overflow or division by zero.
+impl<I: IntegerType, const FRAC: u32> std::ops::Div for Fixed<I, FRAC> {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self {
+        self.checked_div(rhs)
+            .expect("fixed-point division overflowed or divided by zero")
+    }
+}
+//
+//      FORMATTING IMPLS
+//
+impl<I: IntegerType, const FRAC: u32> Display for Fixed<I, FRAC> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.to_f64())
+    }
+}
+
+impl<I: IntegerType, const FRAC: u32> FromStr for Fixed<I, FRAC> {
+    type Err = ParseFloatError;
+
+    fn from_str(s: &str) -> Result<Self, ParseFloatError> {
+        Ok(Self::from_f64(s.parse()?))
+    }
+}
+//
+//      NUMERICTYPE IMPL
+//
+impl<I: IntegerType, const FRAC: u32> NumericType for Fixed<I, FRAC> {
+    fn to_f32(self) -> f32 {
+        self.to_f64() as f32
+    }
+    fn from_f32(value: f32) -> Self {
+        Self::from_f64(value as f64)
+    }
+    fn to_f64(self) -> f64 {
+        self.bits.to_f64() / Self::scale() as f64
+    }
+    fn from_f64(value: f64) -> Self {
+        Self::from_bits(I::from_f64((value * Self::scale() as f64).round()))
+    }
+
+    fn zero() -> Self {
+        Self::from_bits(I::zero())
+    }
+    fn one() -> Self {
+        Self::from_bits(I::one() << FRAC)
+    }
+    fn min_value() -> Self {
+        Self::from_bits(I::min_value())
+    }
+    fn max_value() -> Self {
+        Self::from_bits(I::max_value())
+    }
+
+    fn abs(self) -> Self {
+        Self::from_bits(self.bits.abs())
+    }
+    fn signum(self) -> Self {
+        Self::from_bits(self.bits.signum() << FRAC)
+    }
+
+    fn checked_add(self, rhs: Self) -> Option<Self> {
+        self.bits.checked_add(rhs.bits).map(Self::from_bits)
+    }
+    fn checked_sub(self, rhs: Self) -> Option<Self> {
+        self.bits.checked_sub(rhs.bits).map(Self::from_bits)
+    }
+    fn checked_mul(self, rhs: Self) -> Option<Self> {
+        I::checked_from_i128(self.wide_mul(rhs)?).map(Self::from_bits)
+    }
+    fn checked_div(self, rhs: Self) -> Option<Self> {
+        I::checked_from_i128(self.wide_div(rhs)?).map(Self::from_bits)
+    }
+
+    fn saturating_add(self, rhs: Self) -> Self {
+        Self::from_bits(self.bits.saturating_add(rhs.bits))
+    }
+    fn saturating_sub(self, rhs: Self) -> Self {
+        Self::from_bits(self.bits.saturating_sub(rhs.bits))
+    }
+    fn saturating_mul(self, rhs: Self) -> Self {
+        match self.wide_mul(rhs) {
+            Some(value) => Self::from_wide_saturating(value),
+            None => self.saturated(rhs),
+        }
+    }
+    fn saturating_div(self, rhs: Self) -> Self {
+        assert!(rhs.bits != I::zero(), "attempt to divide by zero");
+        match self.wide_div(rhs) {
+            Some(value) => Self::from_wide_saturating(value),
+            None => self.saturated(rhs),
+        }
+    }
+
+    fn wrapping_add(self, rhs: Self) -> Self {
+        Self::from_bits(self.bits.wrapping_add(rhs.bits))
+    }
+    fn wrapping_sub(self, rhs: Self) -> Self {
+        Self::from_bits(self.bits.wrapping_sub(rhs.bits))
+    }
+    fn wrapping_mul(self, rhs: Self) -> Self {
+        let product = self.bits.to_i128().wrapping_mul(rhs.bits.to_i128());
+        Self::from_bits(I::wrapping_from_i128(product / Self::scale()))
+    }
+    fn wrapping_div(self, rhs: Self) -> Self {
+        let numerator = self.bits.to_i128().wrapping_mul(Self::scale());
+        Self::from_bits(I::wrapping_from_i128(
+            numerator.wrapping_div(rhs.bits.to_i128()),
+        ))
+    }
+}
@@ -0,0 +1,78 @@
+use crate::generics::NumericType;
+
+pub trait FloatType: NumericType + PartialOrd + std::ops::Neg<Output = Self> {
+    fn sqrt(self) -> Self;
+    fn sin(self) -> Self;
+    fn cos(self) -> Self;
+    fn tan(self) -> Self;
+    fn atan2(self, other: Self) -> Self;
+    /// `self * a + b` with a single rounding.
+    fn mul_add(self, a: Self, b: Self) -> Self;
+    fn floor(self) -> Self;
+    fn ceil(self) -> Self;
+    fn round(self) -> Self;
+    fn is_finite(self) -> bool;
+    fn is_nan(self) -> bool;
+    /// The machine epsilon, e.g. `f32::EPSILON`.
+    fn epsilon() -> Self;
+
+    /// Equal within `epsilon()` relative to the larger magnitude, or absolutely for values
+    /// below one.
+    fn approx_eq(self, other: Self) -> bool {
+        let (a, b) = (self.abs(), other.abs());
+        let larger = if a > b { a } else { b };
+        let scale = if larger > Self::one() {
+            larger
+        } else {
+            Self::one()
+        };
+        (self - other).abs() <= Self::epsilon() * scale
+    }
+}
+
+macro_rules! impl_float {
+    ($($t:ty),+) => {
+        $(
+            impl FloatType for $t {
+                fn sqrt(self) -> Self {
+                    <$t>::sqrt(self)
+                }
+                fn sin(self) -> Self {
+                    <$t>::sin(self)
+                }
+                fn cos(self) -> Self {
+                    <$t>::cos(self)
+                }
+                fn tan(self) -> Self {
+                    <$t>::tan(self)
+                }
+                fn atan2(self, other: Self) -> Self {
+                    <$t>::atan2(self, other)
+                }
+                fn mul_add(self, a: Self, b: Self) -> Self {
+                    <$t>::mul_add(self, a, b)
+                }
+                fn floor(self) -> Self {
+                    <$t>::floor(self)
+                }
+                fn ceil(self) -> Self {
+                    <$t>::ceil(self)
+                }
+                fn round(self) -> Self {
+                    <$t>::round(self)
+                }
+                fn is_finite(self) -> bool {
+                    <$t>::is_finite(self)
+                }
+                fn is_nan(self) -> bool {
+                    <$t>::is_nan(self)
+                }
+                fn epsilon() -> Self {
+                    <$t>::EPSILON
+                }
+            }
+        )+
+    };
+}
+
+impl_float!(f32, f64);
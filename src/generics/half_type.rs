@@ -0,0 +1,262 @@
+use std::{fmt::Display, num::ParseFloatError, str::FromStr};
+
+use bytemuck::{Pod, Zeroable};
+
+use crate::generics::{FloatType, NumericType};
+
+const SIGN_MASK: u16 = 0x8000;
+const EXP_MASK: u16 = 0x7c00;
+const MANT_MASK: u16 = 0x03ff;
+
+/// An IEEE 754 binary16 value, stored as its bits.
+///
+/// Conversions to `f32`/`f64` are exact. Conversions from them round to nearest, ties to
+/// even, overflowing to infinity. Arithmetic is done in `f32` and rounded back, which gives
+/// the correctly rounded result for `+ - * /`.
+#[allow(non_camel_case_types)]
+#[repr(transparent)]
+#[derive(Clone, Copy, Default)]
+pub struct f16(u16);
+
+// SAFETY: `f16` is a transparent wrapper around `u16`.
+unsafe impl Zeroable for f16 {}
+unsafe impl Pod for f16 {}
+
+impl f16 {
+    pub const ZERO: f16 = f16(0);
+    pub const ONE: f16 = f16(0x3c00);
+    pub const MIN: f16 = f16(0xfbff);
+    pub const MAX: f16 = f16(0x7bff);
+    pub const EPSILON: f16 = f16(0x1400);
+    pub const INFINITY: f16 = f16(EXP_MASK);
+    pub const NEG_INFINITY: f16 = f16(SIGN_MASK | EXP_MASK);
+    pub const NAN: f16 = f16(0x7e00);
+
+    pub fn from_bits(bits: u16) -> f16 {
+        f16(bits)
+    }
+
+    pub fn to_bits(self) -> u16 {
+        self.0
+    }
+
+    fn from_f64_bits(value: f64) -> f16 {
+        let bits = value.to_bits();
+        let sign = ((bits >> 48) as u16) & SIGN_MASK;
+        let exp = ((bits >> 52) & 0x7ff) as i32;
+        let mant = bits & ((1 << 52) - 1);
+
+        if exp == 0x7ff {
+            return f16(sign | if mant == 0 { EXP_MASK } else { 0x7e00 });
+        }
+        if exp == 0 {
+            // f64 subnormals are far below the smallest f16 subnormal
+            return f16(sign);
+        }
+
+        let e = exp - 1023;
+        if e > 15 {
+            return f16(sign | EXP_MASK);
+        }
+
+        let (full, shift, exp_bits) = if e >= -14 {
+            (mant, 42, ((e + 15) as u64) << 10)
+        } else {
+            // subnormal, counted in units of 2^-24
+            let shift = (28 - e) as u32;
+            if shift >= 64 {
+                return f16(sign);
+            }
+            (mant | 1 << 52, shift, 0)
+        };
+
+        let base = exp_bits | full >> shift;
+        let rem = full & ((1 << shift) - 1);
+        let half = 1 << (shift - 1);
+        let round_up = rem > half || (rem == half && base & 1 == 1);
+
+        // a carry out of the mantissa correctly bumps the exponent, up to infinity
+        f16(sign | (base + round_up as u64) as u16)
+    }
+
+    fn to_f32_bits(self) -> f32 {
+        let sign = ((self.0 & SIGN_MASK) as u32) << 16;
+        let exp = ((self.0 & EXP_MASK) >> 10) as u32;
+        let mant = (self.0 & MANT_MASK) as u32;
+
+        match exp {
+            0 => {
+                let value = mant as f32 * f32::from_bits(0x3380_0000); // 2^-24
+                if sign != 0 { -value } else { value }
+            }
+            0x1f => f32::from_bits(sign | 0x7f80_0000 | mant << 13),
+            _ => f32::from_bits(sign | (exp + 127 - 15) << 23 | mant << 13),
+        }
+    }
+}
+//
+//      OPERATOR IMPLS
+//
+macro_rules! impl_half_op {
+    ($($trait:ident, $fn:ident: $op:tt);+) => {
+        $(
+            impl std::ops::$trait for f16 {
+                type Output = f16;
+
+                fn $fn(self, rhs: f16) -> f16 {
+                    f16::from_f32(self.to_f32() $op rhs.to_f32())
+                }
+            }
+        )+
+    };
+}
+
+impl_half_op!(Add, add: +; Sub, sub: -; Mul, mul: *; Div, div: /);
+
+impl std::ops::Neg for f16 {
+    type Output = f16;
+
+    fn neg(self) -> f16 {
+        f16(self.0 ^ SIGN_MASK)
+    }
+}
+//
+//      COMPARISON IMPLS
+//
+impl PartialEq for f16 {
+    fn eq(&self, other: &f16) -> bool {
+        self.to_f32() == other.to_f32()
+    }
+}
+
+impl PartialOrd for f16 {
+    fn partial_cmp(&self, other: &f16) -> Option<std::cmp::Ordering> {
+        self.to_f32().partial_cmp(&other.to_f32())
+    }
+}
+//
+//      FORMATTING IMPLS
+//
+impl std::fmt::Debug for f16 {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{:?}", self.to_f32())
+    }
+}
+
+impl Display for f16 {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.to_f32())
+    }
+}
+
+impl FromStr for f16 {
+    type Err = ParseFloatError;
+
+    fn from_str(s: &str) -> Result<f16, ParseFloatError> {
+        Ok(f16::from_f64(s.parse()?))
+    }
+}
+//
+//      NUMERICTYPE IMPLS
+//
+macro_rules! impl_half_arith {
+    ($($checked:ident, $saturating:ident, $wrapping:ident: $op:tt);+) => {
+        $(
+            fn $checked(self, rhs: f16) -> Option<f16> {
+                let result = self $op rhs;
+                result.is_finite().then_some(result)
+            }
+            fn $saturating(self, rhs: f16) -> f16 {
+                let result = self $op rhs;
+                if result.is_nan() {
+                    result
+                } else if result > f16::MAX {
+                    f16::MAX
+                } else if result < f16::MIN {
+                    f16::MIN
+                } else {
+                    result
+                }
+            }
+            fn $wrapping(self, rhs: f16) -> f16 {
+                self $op rhs
+            }
+        )+
+    };
+}
+
+impl NumericType for f16 {
+    fn to_f32(self) -> f32 {
+        self.to_f32_bits()
+    }
+    fn from_f32(value: f32) -> f16 {
+        f16::from_f64_bits(value as f64)
+    }
+    fn to_f64(self) -> f64 {
+        self.to_f32_bits() as f64
+    }
+    fn from_f64(value: f64) -> f16 {
+        f16::from_f64_bits(value)
+    }
+
+    fn zero() -> f16 {
+        f16::ZERO
+    }
+    fn one() -> f16 {
+        f16::ONE
+    }
+    fn min_value() -> f16 {
+        f16::MIN
+    }
+    fn max_value() -> f16 {
+        f16::MAX
+    }
+
+    fn abs(self) -> f16 {
+        f16(self.0 & !SIGN_MASK)
+    }
+    fn signum(self) -> f16 {
+        if self.is_nan() {
+            self
+        } else {
+            f16(f16::ONE.0 | self.0 & SIGN_MASK)
+        }
+    }
+
+    impl_half_arith!(
+        checked_add, saturating_add, wrapping_add: +;
+        checked_sub, saturating_sub, wrapping_sub: -;
+        checked_mul, saturating_mul, wrapping_mul: *;
+        checked_div, saturating_div, wrapping_div: /
+    );
+}
+
+macro_rules! impl_half_unary {
+    ($($fn:ident),+) => {
+        $(
+            fn $fn(self) -> f16 {
+                f16::from_f32(self.to_f32().$fn())
+            }
+        )+
+    };
+}
+
+impl FloatType for f16 {
+    impl_half_unary!(sqrt, sin, cos, tan, floor, ceil, round);
+
+    fn atan2(self, other: f16) -> f16 {
+        f16::from_f32(self.to_f32().atan2(other.to_f32()))
+    }
+    fn mul_add(self, a: f16, b: f16) -> f16 {
+        f16::from_f64(self.to_f64() * a.to_f64() + b.to_f64())
+    }
+    fn is_finite(self) -> bool {
+        self.0 & EXP_MASK != EXP_MASK
+    }
+    fn is_nan(self) -> bool {
+        self.0 & EXP_MASK == EXP_MASK && self.0 & MANT_MASK != 0
+    }
+    fn epsilon() -> f16 {
+        f16::EPSILON
+    }
+}
@@ -0,0 +1,62 @@
+use std::hash::Hash;
+
+use crate::generics::NumericType;
+
+pub trait IntegerType:
+    NumericType
+    + Eq
+    + Ord
+    + Hash
+    + std::ops::Rem<Output = Self>
+    + std::ops::BitAnd<Output = Self>
+    + std::ops::BitOr<Output = Self>
+    + std::ops::BitXor<Output = Self>
+    + std::ops::Not<Output = Self>
+    + std::ops::Shl<u32, Output = Self>
+    + std::ops::Shr<u32, Output = Self>
+{
+    const BITS: u32;
+    const SIGNED: bool;
+
+    fn pow(self, exp: u32) -> Self;
+    fn rem_euclid(self, rhs: Self) -> Self;
+    fn count_ones(self) -> u32;
+
+    /// Values of `u128` above `i128::MAX` wrap.
+    fn to_i128(self) -> i128;
+    fn checked_from_i128(value: i128) -> Option<Self>;
+    fn wrapping_from_i128(value: i128) -> Self;
+}
+
+macro_rules! impl_integer {
+    ($signed:expr; $($t:ty),+) => {
+        $(
+            impl IntegerType for $t {
+                const BITS: u32 = <$t>::BITS;
+                const SIGNED: bool = $signed;
+
+                fn pow(self, exp: u32) -> Self {
+                    <$t>::pow(self, exp)
+                }
+                fn rem_euclid(self, rhs: Self) -> Self {
+                    <$t>::rem_euclid(self, rhs)
+                }
+                fn count_ones(self) -> u32 {
+                    <$t>::count_ones(self)
+                }
+                fn to_i128(self) -> i128 {
+                    self as i128
+                }
+                fn checked_from_i128(value: i128) -> Option<Self> {
+                    Self::try_from(value).ok()
+                }
+                fn wrapping_from_i128(value: i128) -> Self {
+                    value as Self
+                }
+            }
+        )+
+    };
+}
+
+impl_integer!(true; i8, i16, i32, i64, i128, isize);
+impl_integer!(false; u8, u16, u32, u64, u128, usize);
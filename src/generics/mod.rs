@@ -1,9 +1,22 @@
 mod byte_type;
+mod fixed_type;
+mod float_type;
+mod half_type;
+mod integer_type;
 mod numeric_collection_type;
 mod numeric_type;
 mod vector_type;
 
-pub use byte_type::Byteable;
-pub use numeric_collection_type::NumericCollectionType;
+pub use byte_type::{ByteError, Byteable, Endian, FromBytes, Varint};
+pub use fixed_type::Fixed;
+pub use float_type::FloatType;
+pub use half_type::f16;
+pub use integer_type::IntegerType;
+pub use numeric_collection_type::{CollectionError, NumericCollectionType};
 pub use numeric_type::NumericType;
-pub use vector_type::VectorType;
+pub use vector_type::{VectorIter, VectorType};
+
+pub use bytemuck::Pod;
+
+#[cfg(feature = "derive")]
+pub use rust_utils_derive::{Byteable, FromBytes};
@@ -1,99 +1,71 @@
-use crate::{
-    generics::{NumericType, VectorType},
-    //graphics::vectors::Vec2,
-};
+use thiserror::Error;
 
+use crate::generics::{NumericType, VectorType};
+//
+//      ERRORS
+//
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum CollectionError {
+    #[error("Expected a collection of length {expected}, found {found}.")]
+    LengthMismatch { expected: usize, found: usize },
+}
+//
+//      TRAITS
+//
+/// Indexable numbers whose length may only be known at runtime.
 pub trait NumericCollectionType<T: NumericType> {
+    /// `None` when the length isn't known up front.
     fn len(&self) -> Option<usize>;
-    fn get(&self, index: usize) -> Option<T>;
-}
-
-impl<T: NumericType> NumericCollectionType<T> for (T, T) {
-    fn len(&self) -> Option<usize> {
-        Some(2)
-    }
-
-    fn get(&self, index: usize) -> Option<T> {
-        match index {
-            0 => Some(self.0),
-            1 => Some(self.1),
-            _ => None,
-        }
-    }
-}
-
-impl<T: NumericType> NumericCollectionType<T> for (T, T, T) {
-    fn len(&self) -> Option<usize> {
-        Some(3)
+    fn is_empty(&self) -> bool {
+        self.len() == Some(0)
     }
+    fn get(&self, index: usize) -> Option<T>;
 
-    fn get(&self, index: usize) -> Option<T> {
-        match index {
-            0 => Some(self.0),
-            1 => Some(self.1),
-            2 => Some(self.2),
-            _ => None,
-        }
+    /// The length, counting elements through `get` when `len` doesn't know it.
+    fn count(&self) -> usize {
+        self.len()
+            .unwrap_or_else(|| (0..).take_while(|&i| self.get(i).is_some()).count())
     }
 }
-
-impl<T: NumericType> NumericCollectionType<T> for (T, T, T, T) {
+//
+//      IMPLS
+//
+impl<V: VectorType> NumericCollectionType<V::Scalar> for V {
     fn len(&self) -> Option<usize> {
-        Some(4)
+        Some(V::LEN)
     }
 
-    fn get(&self, index: usize) -> Option<T> {
-        match index {
-            0 => Some(self.0),
-            1 => Some(self.1),
-            2 => Some(self.2),
-            3 => Some(self.3),
-            _ => None,
-        }
+    fn get(&self, index: usize) -> Option<V::Scalar> {
+        VectorType::get(self, index)
     }
 }
 
-impl<T: NumericType> NumericCollectionType<T> for [T; 2] {
+impl<T: NumericType> NumericCollectionType<T> for [T] {
     fn len(&self) -> Option<usize> {
-        Some(2)
+        Some(<[T]>::len(self))
     }
 
     fn get(&self, index: usize) -> Option<T> {
-        match index {
-            0 => Some(self[0]),
-            1 => Some(self[1]),
-            _ => None,
-        }
+        <[T]>::get(self, index).copied()
     }
 }
 
-impl<T: NumericType> NumericCollectionType<T> for [T; 3] {
+impl<T: NumericType> NumericCollectionType<T> for &[T] {
     fn len(&self) -> Option<usize> {
-        Some(3)
+        Some(<[T]>::len(self))
     }
 
     fn get(&self, index: usize) -> Option<T> {
-        match index {
-            0 => Some(self[0]),
-            1 => Some(self[1]),
-            2 => Some(self[2]),
-            _ => None,
-        }
+        <[T]>::get(self, index).copied()
     }
 }
 
-impl<T: NumericType> NumericCollectionType<T> for [T; 4] {
+impl<T: NumericType> NumericCollectionType<T> for Vec<T> {
     fn len(&self) -> Option<usize> {
-        Some(4)
+        Some(Vec::len(self))
     }
 
     fn get(&self, index: usize) -> Option<T> {
-        match index {
-            0 => Some(self[0]),
-            1 => Some(self[1]),
-            2 => Some(self[2]),
-            3 => Some(self[3]),
-            _ => None,
-        }
+        <[T]>::get(self, index).copied()
     }
 }
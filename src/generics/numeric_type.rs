@@ -20,71 +20,196 @@ pub trait NumericType:
 {
     fn to_f32(self) -> f32;
     fn from_f32(value: f32) -> Self;
-}
+    fn to_f64(self) -> f64;
+    fn from_f64(value: f64) -> Self;
+
+    fn zero() -> Self;
+    fn one() -> Self;
+    /// The smallest finite value, so negative for floats rather than the smallest positive.
+    fn min_value() -> Self;
+    fn max_value() -> Self;
+
+    /// The identity for unsigned types. Signed `MIN` has no positive counterpart, so as with
+    /// std it panics in debug builds and returns `MIN` in release builds.
+    fn abs(self) -> Self;
+    /// `-1`, `0` or `1` for integers (`0` or `1` if unsigned). Floats follow `f32::signum`,
+    /// so zero gives `1.0` or `-1.0` by its sign.
+    fn signum(self) -> Self;
 
-impl NumericType for i32 {
-    fn to_f32(self) -> f32 {
-        self as f32
-    }
+    /// For floats, `None` whenever the result isn't finite.
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+    fn checked_div(self, rhs: Self) -> Option<Self>;
 
-    fn from_f32(value: f32) -> Self {
-        value as Self
-    }
+    /// For floats, infinite results are clamped to the finite range and NaN is kept.
+    /// Integer division by zero panics, as in std.
+    fn saturating_add(self, rhs: Self) -> Self;
+    fn saturating_sub(self, rhs: Self) -> Self;
+    fn saturating_mul(self, rhs: Self) -> Self;
+    fn saturating_div(self, rhs: Self) -> Self;
+
+    /// Floats don't wrap, so these are the plain operators. Integer division by zero
+    /// panics, as in std.
+    fn wrapping_add(self, rhs: Self) -> Self;
+    fn wrapping_sub(self, rhs: Self) -> Self;
+    fn wrapping_mul(self, rhs: Self) -> Self;
+    fn wrapping_div(self, rhs: Self) -> Self;
 }
-impl NumericType for i64 {
-    fn to_f32(self) -> f32 {
-        self as f32
-    }
-    fn from_f32(value: f32) -> Self {
-        value as Self
-    }
+
+macro_rules! impl_numeric_common {
+    () => {
+        fn to_f32(self) -> f32 {
+            self as f32
+        }
+        fn from_f32(value: f32) -> Self {
+            value as Self
+        }
+        fn to_f64(self) -> f64 {
+            self as f64
+        }
+        fn from_f64(value: f64) -> Self {
+            value as Self
+        }
+        fn min_value() -> Self {
+            Self::MIN
+        }
+        fn max_value() -> Self {
+            Self::MAX
+        }
+    };
 }
 
-impl NumericType for f32 {
-    fn to_f32(self) -> f32 {
-        self as f32
-    }
-    fn from_f32(value: f32) -> Self {
-        value as Self
-    }
+macro_rules! impl_numeric_integer_ops {
+    ($t:ty: checked $($op:ident),+) => {
+        $(
+            fn $op(self, rhs: Self) -> Option<Self> {
+                <$t>::$op(self, rhs)
+            }
+        )+
+    };
+    ($t:ty: $($op:ident),+) => {
+        $(
+            fn $op(self, rhs: Self) -> Self {
+                <$t>::$op(self, rhs)
+            }
+        )+
+    };
 }
-impl NumericType for f64 {
-    fn to_f32(self) -> f32 {
-        self as f32
-    }
-    fn from_f32(value: f32) -> Self {
-        value as Self
-    }
+
+macro_rules! impl_numeric_integer {
+    ($t:ty) => {
+        impl_numeric_integer_ops!($t: checked checked_add, checked_sub, checked_mul, checked_div);
+        impl_numeric_integer_ops!(
+            $t: saturating_add,
+            saturating_sub,
+            saturating_mul,
+            saturating_div,
+            wrapping_add,
+            wrapping_sub,
+            wrapping_mul,
+            wrapping_div
+        );
+    };
 }
-impl NumericType for u8 {
-    fn to_f32(self) -> f32 {
-        self as f32
-    }
-    fn from_f32(value: f32) -> Self {
-        value as Self
-    }
+
+macro_rules! impl_numeric_float_ops {
+    ($($checked:ident, $saturating:ident, $wrapping:ident: $op:tt);+) => {
+        $(
+            fn $checked(self, rhs: Self) -> Option<Self> {
+                let result = self $op rhs;
+                result.is_finite().then_some(result)
+            }
+            fn $saturating(self, rhs: Self) -> Self {
+                (self $op rhs).clamp(Self::MIN, Self::MAX)
+            }
+            fn $wrapping(self, rhs: Self) -> Self {
+                self $op rhs
+            }
+        )+
+    };
 }
-impl NumericType for u32 {
-    fn to_f32(self) -> f32 {
-        self as f32
-    }
-    fn from_f32(value: f32) -> Self {
-        value as Self
-    }
+
+macro_rules! impl_numeric_signed {
+    ($($t:ty),+) => {
+        $(
+            impl NumericType for $t {
+                impl_numeric_common!();
+
+                fn zero() -> Self {
+                    0
+                }
+                fn one() -> Self {
+                    1
+                }
+                fn abs(self) -> Self {
+                    <$t>::abs(self)
+                }
+                fn signum(self) -> Self {
+                    <$t>::signum(self)
+                }
+
+                impl_numeric_integer!($t);
+            }
+        )+
+    };
 }
-impl NumericType for u64 {
-    fn to_f32(self) -> f32 {
-        self as f32
-    }
-    fn from_f32(value: f32) -> Self {
-        value as Self
-    }
+
+macro_rules! impl_numeric_unsigned {
+    ($($t:ty),+) => {
+        $(
+            impl NumericType for $t {
+                impl_numeric_common!();
+
+                fn zero() -> Self {
+                    0
+                }
+                fn one() -> Self {
+                    1
+                }
+                fn abs(self) -> Self {
+                    self
+                }
+                fn signum(self) -> Self {
+                    (self != 0) as Self
+                }
+
+                impl_numeric_integer!($t);
+            }
+        )+
+    };
 }
-impl NumericType for usize {
-    fn to_f32(self) -> f32 {
-        self as f32
-    }
-    fn from_f32(value: f32) -> Self {
-        value as Self
-    }
+
+macro_rules! impl_numeric_float {
+    ($($t:ty),+) => {
+        $(
+            impl NumericType for $t {
+                impl_numeric_common!();
+
+                fn zero() -> Self {
+                    0.0
+                }
+                fn one() -> Self {
+                    1.0
+                }
+                fn abs(self) -> Self {
+                    <$t>::abs(self)
+                }
+                fn signum(self) -> Self {
+                    <$t>::signum(self)
+                }
+
+                impl_numeric_float_ops!(
+                    checked_add, saturating_add, wrapping_add: +;
+                    checked_sub, saturating_sub, wrapping_sub: -;
+                    checked_mul, saturating_mul, wrapping_mul: *;
+                    checked_div, saturating_div, wrapping_div: /
+                );
+            }
+        )+
+    };
 }
+
+impl_numeric_signed!(i8, i16, i32, i64, i128, isize);
+impl_numeric_unsigned!(u8, u16, u32, u64, u128, usize);
+impl_numeric_float!(f32, f64);
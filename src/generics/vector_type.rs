@@ -1,15 +1,124 @@
 use bytemuck::Pod;
 
-use crate::generics::{Byteable, NumericType};
-
-pub trait VectorType<T: NumericType> {
-    fn len(&self) -> usize;
-    fn get(&self, index: usize) -> Option<T>;
-    fn to_collection(&self) -> Vec<T> {
-        let mut newvec: Vec<T> = vec![];
-        for i in 0..self.len() {
-            newvec.push(self.get(i).unwrap());
+use crate::generics::{Byteable, CollectionError, NumericCollectionType, NumericType};
+
+/// A fixed-size vector of numbers. Implementors only provide `get` and `from_fn`; everything
+/// else, including `NumericCollectionType`, comes from those.
+pub trait VectorType: Sized + Copy {
+    type Scalar: NumericType;
+    const LEN: usize;
+
+    fn get(&self, index: usize) -> Option<Self::Scalar>;
+    fn from_fn<F: FnMut(usize) -> Self::Scalar>(f: F) -> Self;
+
+    fn len(&self) -> usize {
+        Self::LEN
+    }
+    fn is_empty(&self) -> bool {
+        Self::LEN == 0
+    }
+
+    fn iter(&self) -> VectorIter<Self> {
+        VectorIter {
+            vector: *self,
+            index: 0,
         }
-        return newvec;
+    }
+
+    fn map<F: FnMut(Self::Scalar) -> Self::Scalar>(self, mut f: F) -> Self {
+        Self::from_fn(|i| f(self.get(i).unwrap()))
+    }
+
+    fn zip_with<F>(self, other: Self, mut f: F) -> Self
+    where
+        F: FnMut(Self::Scalar, Self::Scalar) -> Self::Scalar,
+    {
+        Self::from_fn(|i| f(self.get(i).unwrap(), other.get(i).unwrap()))
+    }
+
+    fn fold<B, F: FnMut(B, Self::Scalar) -> B>(self, init: B, f: F) -> B {
+        self.iter().fold(init, f)
+    }
+
+    fn from_collection<C>(collection: C) -> Result<Self, CollectionError>
+    where
+        C: NumericCollectionType<Self::Scalar>,
+    {
+        let found = collection.count();
+        if found != Self::LEN {
+            return Err(CollectionError::LengthMismatch {
+                expected: Self::LEN,
+                found,
+            });
+        }
+        Ok(Self::from_fn(|i| collection.get(i).unwrap()))
+    }
+
+    /// Converts between vector types of the same length and scalar, e.g. a tuple into an
+    /// array. Mismatched lengths fail to compile.
+    fn convert<V: VectorType<Scalar = Self::Scalar>>(self) -> V {
+        const { assert!(Self::LEN == V::LEN, "vector lengths differ") };
+        V::from_fn(|i| self.get(i).unwrap())
+    }
+
+    fn to_collection(&self) -> Vec<Self::Scalar> {
+        self.iter().collect()
     }
 }
+
+pub struct VectorIter<V> {
+    vector: V,
+    index: usize,
+}
+
+impl<V: VectorType> Iterator for VectorIter<V> {
+    type Item = V::Scalar;
+
+    fn next(&mut self) -> Option<V::Scalar> {
+        let value = self.vector.get(self.index)?;
+        self.index += 1;
+        Some(value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = V::LEN.saturating_sub(self.index);
+        (remaining, Some(remaining))
+    }
+}
+
+impl<V: VectorType> ExactSizeIterator for VectorIter<V> {}
+
+impl<T: NumericType, const N: usize> VectorType for [T; N] {
+    type Scalar = T;
+    const LEN: usize = N;
+
+    fn get(&self, index: usize) -> Option<T> {
+        <[T]>::get(self, index).copied()
+    }
+    fn from_fn<F: FnMut(usize) -> T>(f: F) -> Self {
+        std::array::from_fn(f)
+    }
+}
+
+macro_rules! impl_tuple_vector {
+    ($len:expr; $($T:ident: $idx:tt),+) => {
+        impl<T: NumericType> VectorType for ($($T,)+) {
+            type Scalar = T;
+            const LEN: usize = $len;
+
+            fn get(&self, index: usize) -> Option<T> {
+                match index {
+                    $($idx => Some(self.$idx),)+
+                    _ => None,
+                }
+            }
+            fn from_fn<F: FnMut(usize) -> T>(mut f: F) -> Self {
+                ($(f($idx),)+)
+            }
+        }
+    };
+}
+
+impl_tuple_vector!(2; T: 0, T: 1);
+impl_tuple_vector!(3; T: 0, T: 1, T: 2);
+impl_tuple_vector!(4; T: 0, T: 1, T: 2, T: 3);
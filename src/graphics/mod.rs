@@ -1,6 +1,6 @@
 //pub mod camera;
 //pub mod experimental;
-//pub mod vectors;
+pub mod vectors;
 //pub mod voxels;
 pub mod egui_helpers;
 pub mod wgpu_helpers;
@@ -1,14 +1,17 @@
 use crate::{
-    generics::{NumericCollectionType, NumericType},
+    generics::{CollectionError, FloatType, NumericCollectionType, NumericType, VectorType},
     graphics::vectors::{Vec2, Vec3, Vec4},
 };
 
+// Only fixed-length vectors, so a length mismatch fails to compile instead of panicking.
+// Slices and `Vec`s go through the `checked_*` methods.
 macro_rules! impl_add {
     ($VecType:ident { $($field:ident: $idx:expr),+ }) => {
-        impl<T: NumericType, U: NumericCollectionType<T>> std::ops::Add<U> for $VecType<T> {
+        impl<T: NumericType, U: VectorType<Scalar = T>> std::ops::Add<U> for $VecType<T> {
             type Output = Self;
 
             fn add(self, other: U) -> Self {
+                const { assert!(U::LEN == <Self as VectorType>::LEN, "vector lengths differ") };
                 Self {
                     $($field: self.$field + other.get($idx).unwrap()),+
                 }
@@ -19,10 +22,11 @@ macro_rules! impl_add {
 
 macro_rules! impl_sub{
     ($VecType:ident { $($field:ident: $idx:expr),+ }) => {
-        impl<T: NumericType, U: NumericCollectionType<T>> std::ops::Sub<U> for $VecType<T> {
+        impl<T: NumericType, U: VectorType<Scalar = T>> std::ops::Sub<U> for $VecType<T> {
             type Output = Self;
 
             fn sub(self, other: U) -> Self {
+                const { assert!(U::LEN == <Self as VectorType>::LEN, "vector lengths differ") };
                 Self {
                     $($field: self.$field - other.get($idx).unwrap()),+
                 }
@@ -33,10 +37,11 @@ macro_rules! impl_sub{
 
 macro_rules! impl_mul{
     ($VecType:ident { $($field:ident: $idx:expr),+ }) => {
-        impl<T: NumericType, U: NumericCollectionType<T>> std::ops::Mul<U> for $VecType<T> {
+        impl<T: NumericType, U: VectorType<Scalar = T>> std::ops::Mul<U> for $VecType<T> {
             type Output = Self;
 
             fn mul(self, other: U) -> Self {
+                const { assert!(U::LEN == <Self as VectorType>::LEN, "vector lengths differ") };
                 Self {
                     $($field: self.$field * other.get($idx).unwrap()),+
                 }
@@ -47,10 +52,11 @@ macro_rules! impl_mul{
 
 macro_rules! impl_div{
     ($VecType:ident { $($field:ident: $idx:expr),+ }) => {
-        impl<T: NumericType, U: NumericCollectionType<T>> std::ops::Div<U> for $VecType<T> {
+        impl<T: NumericType, U: VectorType<Scalar = T>> std::ops::Div<U> for $VecType<T> {
             type Output = Self;
 
             fn div(self, other: U) -> Self {
+                const { assert!(U::LEN == <Self as VectorType>::LEN, "vector lengths differ") };
                 Self {
                     $($field: self.$field / other.get($idx).unwrap()),+
                 }
@@ -59,11 +65,49 @@ macro_rules! impl_div{
     };
 }
 
+macro_rules! impl_checked {
+    ($VecType:ident { $($field:ident: $idx:expr),+ }) => {
+        impl<T: NumericType> $VecType<T> {
+            impl_checked!(@op checked_add, $($field: $idx),+);
+            impl_checked!(@op checked_sub, $($field: $idx),+);
+            impl_checked!(@op checked_mul, $($field: $idx),+);
+            impl_checked!(@op checked_div, $($field: $idx),+);
+        }
+    };
+    (@op $op:ident, $($field:ident: $idx:expr),+) => {
+        /// Component-wise, returning `None` if any component overflows or divides by zero.
+        /// `other` must have exactly as many elements as `Self`.
+        pub fn $op<U: NumericCollectionType<T>>(
+            self,
+            other: U,
+        ) -> Result<Option<Self>, CollectionError> {
+            let found = other.count();
+            if found != <Self as VectorType>::LEN {
+                return Err(CollectionError::LengthMismatch {
+                    expected: <Self as VectorType>::LEN,
+                    found,
+                });
+            }
+            Ok((|| {
+                Some(Self {
+                    $($field: self.$field.$op(other.get($idx)?)?),+
+                })
+            })())
+        }
+    };
+}
+
 macro_rules! impl_length {
     ($t:ident {$($field:ident),+}) => {
         impl<T: NumericType> $t<T> {
-            pub fn length(&self) -> f32 {
-                (0.0 $(+ (self.$field * self.$field).to_f32())+).sqrt()
+            pub fn length_squared(&self) -> T {
+                T::zero() $(+ self.$field * self.$field)+
+            }
+        }
+
+        impl<T: FloatType> $t<T> {
+            pub fn length(&self) -> T {
+                self.length_squared().sqrt()
             }
         }
     };
@@ -71,9 +115,9 @@ macro_rules! impl_length {
 
 macro_rules! impl_normalize {
     ($VecType:ident {$($field:ident), +}) => {
-        impl<T: NumericType> $VecType<T> {
+        impl<T: FloatType> $VecType<T> {
             pub fn normalize(&self) -> Self {
-                let len = T::from_f32(self.length());
+                let len = self.length();
                 Self{
                     $($field: (self.$field / len)),+
                 }
@@ -118,6 +162,15 @@ impl_div!(Vec4 {
     w: 3
 });
 
+impl_checked!(Vec2 { x: 0, y: 1 });
+impl_checked!(Vec3 { x: 0, y: 1, z: 2 });
+impl_checked!(Vec4 {
+    x: 0,
+    y: 1,
+    z: 2,
+    w: 3
+});
+
 impl_length!(Vec2 { x, y });
 impl_length!(Vec3 { x, y, z });
 impl_length!(Vec4 { x, y, z, w });
@@ -125,3 +178,38 @@ impl_length!(Vec4 { x, y, z, w });
 impl_normalize!(Vec2 { x, y });
 impl_normalize!(Vec3 { x, y, z });
 impl_normalize!(Vec4 { x, y, z, w });
+//
+//      TESTS
+//
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Checked = Result<Option<(u8, u8)>, CollectionError>;
+
+    fn parts(result: Result<Option<Vec2<u8>>, CollectionError>) -> Checked {
+        result.map(|v| v.map(|v| (v.x, v.y)))
+    }
+
+    #[test]
+    fn checked_ops_need_matching_lengths() {
+        let v = Vec2 { x: 1u8, y: 2 };
+        assert_eq!(
+            parts(v.checked_add(vec![1, 2, 3])),
+            Err(CollectionError::LengthMismatch {
+                expected: 2,
+                found: 3
+            })
+        );
+        assert_eq!(
+            parts(v.checked_add(vec![1])),
+            Err(CollectionError::LengthMismatch {
+                expected: 2,
+                found: 1
+            })
+        );
+        assert_eq!(parts(v.checked_add(vec![1, 2])), Ok(Some((2, 4))));
+        assert_eq!(parts(v.checked_add(Vec2 { x: 255, y: 0 })), Ok(None));
+        assert_eq!(parts(v.checked_div([0u8, 1].as_slice())), Ok(None));
+    }
+}
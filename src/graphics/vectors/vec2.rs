@@ -1,4 +1,7 @@
-use crate::generics::{NumericCollectionType, NumericType, VectorType};
+use crate::generics::{
+    ByteError, Byteable, CollectionError, Endian, FromBytes, NumericCollectionType, NumericType,
+    VectorType,
+};
 
 #[repr(C)]
 #[derive(Clone, Copy)]
@@ -7,10 +10,10 @@ pub struct Vec2<T: NumericType> {
     pub y: T,
 }
 
-impl<T: NumericType> VectorType<T> for Vec2<T> {
-    fn len(&self) -> usize {
-        2
-    }
+impl<T: NumericType> VectorType for Vec2<T> {
+    type Scalar = T;
+    const LEN: usize = 2;
+
     fn get(&self, index: usize) -> Option<T> {
         match index {
             0 => Some(self.x),
@@ -18,18 +21,32 @@ impl<T: NumericType> VectorType<T> for Vec2<T> {
             _ => None,
         }
     }
+    fn from_fn<F: FnMut(usize) -> T>(mut f: F) -> Self {
+        Self { x: f(0), y: f(1) }
+    }
 }
 
-impl<T: NumericType> NumericCollectionType<T> for Vec2<T> {
-    fn len(&self) -> Option<usize> {
-        Some(2)
+impl<T: NumericType> From<(T, T)> for Vec2<T> {
+    fn from((x, y): (T, T)) -> Self {
+        Self::new(x, y)
     }
-    fn get(&self, index: usize) -> Option<T> {
-        match index {
-            0 => Some(self.x),
-            1 => Some(self.y),
-            _ => None,
-        }
+}
+
+impl<T: NumericType> From<[T; 2]> for Vec2<T> {
+    fn from(a: [T; 2]) -> Self {
+        Self::new(a[0], a[1])
+    }
+}
+
+impl<T: NumericType> From<Vec2<T>> for (T, T) {
+    fn from(v: Vec2<T>) -> Self {
+        (v.x, v.y)
+    }
+}
+
+impl<T: NumericType> From<Vec2<T>> for [T; 2] {
+    fn from(v: Vec2<T>) -> Self {
+        [v.x, v.y]
     }
 }
 
@@ -38,16 +55,32 @@ impl<T: NumericType> Vec2<T> {
         Self { x, y }
     }
 
-    pub fn from_collection<Z: NumericCollectionType<T>>(collection: Z) -> Result<Self, String> {
-        if collection.len() != Some(2) {
-            Err(String::from(
-                "invalid collection length. cannot convert to Vec2",
-            ))
-        } else {
-            Ok(Self {
-                x: collection.get(0).unwrap(),
-                y: collection.get(1).unwrap(),
-            })
-        }
+    pub fn from_collection<Z: NumericCollectionType<T>>(
+        collection: Z,
+    ) -> Result<Self, CollectionError> {
+        <Self as VectorType>::from_collection(collection)
+    }
+}
+
+impl<T: NumericType> Byteable for Vec2<T> {
+    fn to_bytes(self) -> Vec<u8> {
+        self.copy_bytes()
+    }
+    fn copy_bytes(&self) -> Vec<u8> {
+        self.copy_bytes_endian(Endian::Native)
+    }
+    fn copy_bytes_endian(&self, endian: Endian) -> Vec<u8> {
+        (self.x, self.y).copy_bytes_endian(endian)
+    }
+}
+
+impl<T: NumericType> FromBytes for Vec2<T> {
+    fn read_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), ByteError> {
+        Self::read_bytes_endian(bytes, Endian::Native)
+    }
+
+    fn read_bytes_endian(bytes: &[u8], endian: Endian) -> Result<(Self, &[u8]), ByteError> {
+        let ((x, y), rest) = <(T, T)>::read_bytes_endian(bytes, endian)?;
+        Ok((Self::new(x, y), rest))
     }
 }
@@ -1,5 +1,7 @@
-use crate::generics::{NumericCollectionType, NumericType, VectorType};
-use crate::graphics::wgpu_helpers::Vertex;
+use crate::generics::{
+    ByteError, Byteable, CollectionError, Endian, FromBytes, NumericCollectionType, NumericType,
+    VectorType,
+};
 
 #[derive(Clone, Copy, PartialEq)]
 pub struct Vec3<T: NumericType> {
@@ -8,10 +10,10 @@ pub struct Vec3<T: NumericType> {
     pub z: T,
 }
 
-impl<T: NumericType> VectorType<T> for Vec3<T> {
-    fn len(&self) -> usize {
-        3
-    }
+impl<T: NumericType> VectorType for Vec3<T> {
+    type Scalar = T;
+    const LEN: usize = 3;
+
     fn get(&self, index: usize) -> Option<T> {
         match index {
             0 => Some(self.x),
@@ -20,19 +22,36 @@ impl<T: NumericType> VectorType<T> for Vec3<T> {
             _ => None,
         }
     }
+    fn from_fn<F: FnMut(usize) -> T>(mut f: F) -> Self {
+        Self {
+            x: f(0),
+            y: f(1),
+            z: f(2),
+        }
+    }
 }
 
-impl<T: NumericType> NumericCollectionType<T> for Vec3<T> {
-    fn len(&self) -> Option<usize> {
-        Some(3)
+impl<T: NumericType> From<(T, T, T)> for Vec3<T> {
+    fn from((x, y, z): (T, T, T)) -> Self {
+        Self::new(x, y, z)
     }
-    fn get(&self, index: usize) -> Option<T> {
-        match index {
-            0 => Some(self.x),
-            1 => Some(self.y),
-            2 => Some(self.z),
-            _ => None,
-        }
+}
+
+impl<T: NumericType> From<[T; 3]> for Vec3<T> {
+    fn from(a: [T; 3]) -> Self {
+        Self::new(a[0], a[1], a[2])
+    }
+}
+
+impl<T: NumericType> From<Vec3<T>> for (T, T, T) {
+    fn from(v: Vec3<T>) -> Self {
+        (v.x, v.y, v.z)
+    }
+}
+
+impl<T: NumericType> From<Vec3<T>> for [T; 3] {
+    fn from(v: Vec3<T>) -> Self {
+        [v.x, v.y, v.z]
     }
 }
 
@@ -41,18 +60,10 @@ impl<T: NumericType> Vec3<T> {
         Self { x, y, z }
     }
 
-    pub fn from_collection<Z: NumericCollectionType<T>>(collection: Z) -> Result<Self, String> {
-        if collection.len() != Some(3) {
-            Err(String::from(
-                "invalid collection length. cannot convert to Vec3",
-            ))
-        } else {
-            Ok(Self {
-                x: collection.get(0).unwrap(),
-                y: collection.get(1).unwrap(),
-                z: collection.get(2).unwrap(),
-            })
-        }
+    pub fn from_collection<Z: NumericCollectionType<T>>(
+        collection: Z,
+    ) -> Result<Self, CollectionError> {
+        <Self as VectorType>::from_collection(collection)
     }
 
     pub fn cross(&self, other: Vec3<T>) -> Self {
@@ -63,3 +74,26 @@ impl<T: NumericType> Vec3<T> {
         }
     }
 }
+
+impl<T: NumericType> Byteable for Vec3<T> {
+    fn to_bytes(self) -> Vec<u8> {
+        self.copy_bytes()
+    }
+    fn copy_bytes(&self) -> Vec<u8> {
+        self.copy_bytes_endian(Endian::Native)
+    }
+    fn copy_bytes_endian(&self, endian: Endian) -> Vec<u8> {
+        (self.x, self.y, self.z).copy_bytes_endian(endian)
+    }
+}
+
+impl<T: NumericType> FromBytes for Vec3<T> {
+    fn read_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), ByteError> {
+        Self::read_bytes_endian(bytes, Endian::Native)
+    }
+
+    fn read_bytes_endian(bytes: &[u8], endian: Endian) -> Result<(Self, &[u8]), ByteError> {
+        let ((x, y, z), rest) = <(T, T, T)>::read_bytes_endian(bytes, endian)?;
+        Ok((Self::new(x, y, z), rest))
+    }
+}
@@ -1,4 +1,7 @@
-use crate::generics::{NumericCollectionType, NumericType, VectorType};
+use crate::generics::{
+    ByteError, Byteable, CollectionError, Endian, FromBytes, NumericCollectionType, NumericType,
+    VectorType,
+};
 
 #[derive(Clone, Copy, PartialEq)]
 pub struct Vec4<T: NumericType> {
@@ -8,10 +11,10 @@ pub struct Vec4<T: NumericType> {
     pub w: T,
 }
 
-impl<T: NumericType> VectorType<T> for Vec4<T> {
-    fn len(&self) -> usize {
-        4
-    }
+impl<T: NumericType> VectorType for Vec4<T> {
+    type Scalar = T;
+    const LEN: usize = 4;
+
     fn get(&self, index: usize) -> Option<T> {
         match index {
             0 => Some(self.x),
@@ -21,20 +24,37 @@ impl<T: NumericType> VectorType<T> for Vec4<T> {
             _ => None,
         }
     }
+    fn from_fn<F: FnMut(usize) -> T>(mut f: F) -> Self {
+        Self {
+            x: f(0),
+            y: f(1),
+            z: f(2),
+            w: f(3),
+        }
+    }
 }
 
-impl<T: NumericType> NumericCollectionType<T> for Vec4<T> {
-    fn len(&self) -> Option<usize> {
-        Some(4)
+impl<T: NumericType> From<(T, T, T, T)> for Vec4<T> {
+    fn from((x, y, z, w): (T, T, T, T)) -> Self {
+        Self::new(x, y, z, w)
     }
-    fn get(&self, index: usize) -> Option<T> {
-        match index {
-            0 => Some(self.x),
-            1 => Some(self.y),
-            2 => Some(self.z),
-            3 => Some(self.w),
-            _ => None,
-        }
+}
+
+impl<T: NumericType> From<[T; 4]> for Vec4<T> {
+    fn from(a: [T; 4]) -> Self {
+        Self::new(a[0], a[1], a[2], a[3])
+    }
+}
+
+impl<T: NumericType> From<Vec4<T>> for (T, T, T, T) {
+    fn from(v: Vec4<T>) -> Self {
+        (v.x, v.y, v.z, v.w)
+    }
+}
+
+impl<T: NumericType> From<Vec4<T>> for [T; 4] {
+    fn from(v: Vec4<T>) -> Self {
+        [v.x, v.y, v.z, v.w]
     }
 }
 
@@ -43,18 +63,32 @@ impl<T: NumericType> Vec4<T> {
         Self { x, y, z, w }
     }
 
-    pub fn from_collection<Z: NumericCollectionType<T>>(collection: Z) -> Result<Self, String> {
-        if collection.len() != Some(4) {
-            Err(String::from(
-                "invalid collection length. cannot convert to Vec4",
-            ))
-        } else {
-            Ok(Self {
-                x: collection.get(0).unwrap(),
-                y: collection.get(1).unwrap(),
-                z: collection.get(2).unwrap(),
-                w: collection.get(3).unwrap(),
-            })
-        }
+    pub fn from_collection<Z: NumericCollectionType<T>>(
+        collection: Z,
+    ) -> Result<Self, CollectionError> {
+        <Self as VectorType>::from_collection(collection)
+    }
+}
+
+impl<T: NumericType> Byteable for Vec4<T> {
+    fn to_bytes(self) -> Vec<u8> {
+        self.copy_bytes()
+    }
+    fn copy_bytes(&self) -> Vec<u8> {
+        self.copy_bytes_endian(Endian::Native)
+    }
+    fn copy_bytes_endian(&self, endian: Endian) -> Vec<u8> {
+        (self.x, self.y, self.z, self.w).copy_bytes_endian(endian)
+    }
+}
+
+impl<T: NumericType> FromBytes for Vec4<T> {
+    fn read_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), ByteError> {
+        Self::read_bytes_endian(bytes, Endian::Native)
+    }
+
+    fn read_bytes_endian(bytes: &[u8], endian: Endian) -> Result<(Self, &[u8]), ByteError> {
+        let ((x, y, z, w), rest) = <(T, T, T, T)>::read_bytes_endian(bytes, endian)?;
+        Ok((Self::new(x, y, z, w), rest))
     }
 }
@@ -1,8 +1,14 @@
 #![allow(unused)]
 
+#[cfg(all(test, feature = "derive"))]
+extern crate self as rust_utils;
+
 #[cfg(feature = "graphics")]
 pub mod graphics;
 
 pub mod datastructures;
 pub mod extensions;
 pub mod generics;
+pub mod parsing;
+
+pub use parsing::Splitter;
@@ -0,0 +1,369 @@
+use std::{collections::HashMap, ops::Range, str::FromStr};
+
+use thiserror::Error;
+
+use crate::extensions::{DelimiterSet, StringExt};
+use crate::parsing::rules::{RuleMap, RuleMapError};
+//
+//      ERRORS
+//
+/// Spans are byte ranges into the parsed source.
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum ExprError {
+    #[error("Unexpected {found:?} at byte {}.", span.start)]
+    UnexpectedInputError { found: String, span: Range<usize> },
+    #[error("Unexpected end of expression at byte {0}.")]
+    UnexpectedEndError(usize),
+    #[error("Parenthesis at byte {0} is never closed.")]
+    UnclosedError(usize),
+    #[error("Invalid number {text:?} at byte {}.", span.start)]
+    NumberError { text: String, span: Range<usize> },
+    #[error("Unknown variable {name:?} at byte {}.", span.start)]
+    UnknownVariableError { name: String, span: Range<usize> },
+    #[error("{error} (bytes {}..{})", span.start, span.end)]
+    RuleError {
+        error: RuleMapError,
+        span: Range<usize>,
+    },
+}
+use ExprError as E;
+//
+//      STRUCTS
+//
+/// What an operator or function dispatches on in the evaluator's `RuleMap`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ExprToken {
+    Infix(String),
+    Prefix(String),
+    Function(String),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Associativity {
+    Left,
+    Right,
+}
+
+/// A parsed expression, which can be evaluated any number of times.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expr<V> {
+    Value(V, Range<usize>),
+    Variable(String, Range<usize>),
+    Call {
+        token: ExprToken,
+        args: Vec<Expr<V>>,
+        span: Range<usize>,
+    },
+}
+
+/// Parses infix expressions over values `V` and evaluates them through a `RuleMap`.
+///
+/// Numbers are parsed with `V::from_str`, names followed by `(` are function calls and other
+/// names are variables. Higher precedence binds tighter. The default operators are `+` and
+/// `-` at 1, `*`, `/` and `%` at 2, prefix `-` at 3 and right-associative `^` at 4, and each
+/// needs a rule in the map to be evaluated.
+pub struct Evaluator<V> {
+    rules: RuleMap<ExprToken, V, V>,
+    infix: HashMap<String, (u8, Associativity)>,
+    prefix: HashMap<String, u8>,
+}
+
+struct ExprParser<'a, V> {
+    evaluator: &'a Evaluator<V>,
+    source: &'a str,
+    position: usize,
+    end: usize,
+}
+//
+//      STRUCT IMPLS
+//
+impl ExprToken {
+    pub fn infix(symbol: &str) -> ExprToken {
+        ExprToken::Infix(symbol.to_string())
+    }
+
+    pub fn prefix(symbol: &str) -> ExprToken {
+        ExprToken::Prefix(symbol.to_string())
+    }
+
+    pub fn function(name: &str) -> ExprToken {
+        ExprToken::Function(name.to_string())
+    }
+}
+
+impl<V> Expr<V> {
+    fn span_mut(&mut self) -> &mut Range<usize> {
+        match self {
+            Expr::Value(_, span) | Expr::Variable(_, span) | Expr::Call { span, .. } => span,
+        }
+    }
+
+    pub fn span(&self) -> Range<usize> {
+        match self {
+            Expr::Value(_, span) | Expr::Variable(_, span) | Expr::Call { span, .. } => {
+                span.clone()
+            }
+        }
+    }
+}
+
+impl<V: FromStr + Clone> Evaluator<V> {
+    pub fn new(rules: RuleMap<ExprToken, V, V>) -> Self {
+        let infix = [
+            ("+", 1, Associativity::Left),
+            ("-", 1, Associativity::Left),
+            ("*", 2, Associativity::Left),
+            ("/", 2, Associativity::Left),
+            ("%", 2, Associativity::Left),
+            ("^", 4, Associativity::Right),
+        ];
+        Self {
+            rules,
+            infix: infix
+                .into_iter()
+                .map(|(symbol, precedence, assoc)| (symbol.to_string(), (precedence, assoc)))
+                .collect(),
+            prefix: HashMap::from([("-".to_string(), 3)]),
+        }
+    }
+
+    /// Adds or replaces an infix operator.
+    pub fn with_infix(mut self, symbol: &str, precedence: u8, assoc: Associativity) -> Self {
+        self.infix.insert(symbol.to_string(), (precedence, assoc));
+        self
+    }
+
+    /// Adds or replaces a prefix operator, which binds operators of at least `precedence`.
+    pub fn with_prefix(mut self, symbol: &str, precedence: u8) -> Self {
+        self.prefix.insert(symbol.to_string(), precedence);
+        self
+    }
+
+    pub fn rules(&self) -> &RuleMap<ExprToken, V, V> {
+        &self.rules
+    }
+
+    pub fn rules_mut(&mut self) -> &mut RuleMap<ExprToken, V, V> {
+        &mut self.rules
+    }
+
+    pub fn parse(&self, source: &str) -> Result<Expr<V>, ExprError> {
+        ExprParser {
+            evaluator: self,
+            source,
+            position: 0,
+            end: source.len(),
+        }
+        .parse_all()
+    }
+
+    pub fn evaluate(
+        &self,
+        expr: &Expr<V>,
+        variables: &dyn Fn(&str) -> Option<V>,
+    ) -> Result<V, ExprError> {
+        match expr {
+            Expr::Value(value, _) => Ok(value.clone()),
+            Expr::Variable(name, span) => variables(name).ok_or_else(|| E::UnknownVariableError {
+                name: name.clone(),
+                span: span.clone(),
+            }),
+            Expr::Call { token, args, span } => {
+                let args = args
+                    .iter()
+                    .map(|arg| self.evaluate(arg, variables))
+                    .collect::<Result<Vec<V>, ExprError>>()?;
+                self.rules
+                    .evaluate(token, &args)
+                    .map_err(|error| E::RuleError {
+                        error,
+                        span: span.clone(),
+                    })
+            }
+        }
+    }
+
+    pub fn evaluate_str(
+        &self,
+        source: &str,
+        variables: &dyn Fn(&str) -> Option<V>,
+    ) -> Result<V, ExprError> {
+        self.evaluate(&self.parse(source)?, variables)
+    }
+}
+
+impl<'a, V: FromStr + Clone> ExprParser<'a, V> {
+    //
+    //      PRIVATE
+    //
+    fn rest(&self) -> &'a str {
+        &self.source[self.position..self.end]
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.position += rest.len() - rest.trim_start().len();
+    }
+
+    fn take_while(&mut self, mut f: impl FnMut(char) -> bool) -> Range<usize> {
+        let start = self.position;
+        self.position += self.rest().find(|c| !f(c)).unwrap_or(self.rest().len());
+        start..self.position
+    }
+
+    fn unexpected(&self) -> ExprError {
+        match self.rest().chars().next() {
+            Some(c) => E::UnexpectedInputError {
+                found: c.to_string(),
+                span: self.position..self.position + c.len_utf8(),
+            },
+            None => E::UnexpectedEndError(self.position),
+        }
+    }
+
+    fn longest_symbol<'m, T>(&self, symbols: &'m HashMap<String, T>) -> Option<(&'m str, &'m T)> {
+        symbols
+            .iter()
+            .filter(|(symbol, _)| self.rest().starts_with(symbol.as_str()))
+            .max_by_key(|(symbol, _)| symbol.len())
+            .map(|(symbol, value)| (symbol.as_str(), value))
+    }
+
+    /// Parses `start..end` of the source on its own, as the inside of a group.
+    fn parse_range(&self, start: usize, end: usize) -> Result<Expr<V>, ExprError> {
+        ExprParser {
+            evaluator: self.evaluator,
+            source: self.source,
+            position: start,
+            end,
+        }
+        .parse_all()
+    }
+
+    /// Byte offset of the parenthesis closing the one at the current position.
+    fn closing_paren(&self) -> Result<usize, ExprError> {
+        self.source
+            .find_next_delimiter_index(self.position, ('(', ')'))
+            .filter(|&close| close < self.end)
+            .ok_or(E::UnclosedError(self.position))
+    }
+    //
+    //      GRAMMAR
+    //
+    fn parse_all(mut self) -> Result<Expr<V>, ExprError> {
+        let expr = self.parse_binary(0)?;
+        self.skip_whitespace();
+        match self.position < self.end {
+            true => Err(self.unexpected()),
+            false => Ok(expr),
+        }
+    }
+
+    fn parse_binary(&mut self, min_precedence: u8) -> Result<Expr<V>, ExprError> {
+        let mut lhs = self.parse_unary()?;
+        loop {
+            self.skip_whitespace();
+            let Some((symbol, &(precedence, assoc))) = self.longest_symbol(&self.evaluator.infix)
+            else {
+                break;
+            };
+            if precedence < min_precedence {
+                break;
+            }
+
+            self.position += symbol.len();
+            let next_precedence = match assoc {
+                Associativity::Left => precedence + 1,
+                Associativity::Right => precedence,
+            };
+            let rhs = self.parse_binary(next_precedence)?;
+            let span = lhs.span().start..rhs.span().end;
+            lhs = Expr::Call {
+                token: ExprToken::infix(symbol),
+                args: vec![lhs, rhs],
+                span,
+            };
+        }
+        Ok(lhs)
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr<V>, ExprError> {
+        self.skip_whitespace();
+        let Some((symbol, &precedence)) = self.longest_symbol(&self.evaluator.prefix) else {
+            return self.parse_primary();
+        };
+
+        let start = self.position;
+        self.position += symbol.len();
+        let operand = self.parse_binary(precedence)?;
+        Ok(Expr::Call {
+            token: ExprToken::prefix(symbol),
+            span: start..operand.span().end,
+            args: vec![operand],
+        })
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr<V>, ExprError> {
+        let start = self.position;
+        match self.rest().chars().next() {
+            Some('(') => {
+                let close = self.closing_paren()?;
+                self.position = close + 1;
+                // a group spans its parentheses, so errors point at what was written
+                let mut group = self.parse_range(start + 1, close)?;
+                *group.span_mut() = start..self.position;
+                Ok(group)
+            }
+            Some(c) if c.is_ascii_digit() || c == '.' => {
+                let mut prev = c;
+                let span = self.take_while(|c| {
+                    let sign = matches!(c, '+' | '-') && matches!(prev, 'e' | 'E');
+                    prev = c;
+                    c.is_ascii_alphanumeric() || c == '.' || sign
+                });
+                let text = &self.source[span.clone()];
+                match text.parse() {
+                    Ok(value) => Ok(Expr::Value(value, span)),
+                    Err(_) => Err(E::NumberError {
+                        text: text.to_string(),
+                        span,
+                    }),
+                }
+            }
+            Some(c) if c.is_alphabetic() || c == '_' => {
+                let span = self.take_while(|c| c.is_alphanumeric() || c == '_');
+                let name = &self.source[span.clone()];
+                match self.rest().starts_with('(') {
+                    true => self.parse_call(name, start),
+                    false => Ok(Expr::Variable(name.to_string(), span)),
+                }
+            }
+            _ => Err(self.unexpected()),
+        }
+    }
+
+    fn parse_call(&mut self, name: &str, start: usize) -> Result<Expr<V>, ExprError> {
+        let open = self.position;
+        let close = self.closing_paren()?;
+        self.position = close + 1;
+
+        let inner = &self.source[open + 1..close];
+        let mut args = vec![];
+        if !inner.trim().is_empty() {
+            let pieces = inner
+                .split_top_level(',', &DelimiterSet::new(&[('(', ')')]))
+                .map_err(|_| E::UnclosedError(open))?;
+            let mut piece_start = open + 1;
+            for piece in pieces {
+                args.push(self.parse_range(piece_start, piece_start + piece.len())?);
+                piece_start += piece.len() + 1;
+            }
+        }
+
+        Ok(Expr::Call {
+            token: ExprToken::function(name),
+            args,
+            span: start..self.position,
+        })
+    }
+}
@@ -0,0 +1,171 @@
+use std::ops::Range;
+
+use thiserror::Error;
+
+use crate::parsing::rules::{Lexeme, MatchRuleResult, MatchRuleSet, RuleSet};
+//
+//      ERRORS
+//
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum LexError {
+    #[error("Unmatched input {text:?} at line {line}, column {column}.")]
+    UnmatchedInputError {
+        text: String,
+        line: usize,
+        column: usize,
+    },
+}
+//
+//      STRUCTS
+//
+type SkipFn<T> = Box<dyn Fn(&T) -> bool>;
+
+/// Splits input into tokens with a set of `Lexeme` rules.
+///
+/// At each position every rule is tested and the longest match wins, with ties going to the
+/// higher priority rule and then the earlier inserted one. Matches of zero bytes, or that end
+/// inside a character, don't count. Input no rule matches becomes an error token running up to
+/// the next position where one does.
+pub struct Lexer<T> {
+    rules: MatchRuleSet<str, Lexeme<T>>,
+    skip: Option<SkipFn<T>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Token<'a, T> {
+    /// `None` for unmatched input.
+    pub value: Option<T>,
+    pub text: &'a str,
+    /// Byte offsets into the input.
+    pub span: Range<usize>,
+    /// 1-based, with the column counted in characters.
+    pub line: usize,
+    pub column: usize,
+}
+
+/// Lazy tokens of an input.
+pub struct Tokens<'a, T> {
+    lexer: &'a Lexer<T>,
+    input: &'a str,
+    position: usize,
+    line: usize,
+    column: usize,
+}
+//
+//      STRUCT IMPLS
+//
+impl<T> Lexer<T> {
+    pub fn new(rules: MatchRuleSet<str, Lexeme<T>>) -> Self {
+        Self { rules, skip: None }
+    }
+
+    /// Drops tokens whose value passes `skip`, such as whitespace and comments. They still
+    /// take part in finding the longest match.
+    pub fn with_skip(mut self, skip: impl Fn(&T) -> bool + 'static) -> Self {
+        self.skip = Some(Box::new(skip));
+        self
+    }
+
+    pub fn rules(&self) -> &MatchRuleSet<str, Lexeme<T>> {
+        &self.rules
+    }
+
+    pub fn tokens<'a>(&'a self, input: &'a str) -> Tokens<'a, T> {
+        Tokens {
+            lexer: self,
+            input,
+            position: 0,
+            line: 1,
+            column: 1,
+        }
+    }
+
+    /// Fails on the first error token.
+    pub fn tokenize<'a>(&'a self, input: &'a str) -> Result<Vec<Token<'a, T>>, LexError> {
+        self.tokens(input)
+            .map(|token| match token.value {
+                Some(_) => Ok(token),
+                None => Err(LexError::UnmatchedInputError {
+                    text: token.text.to_string(),
+                    line: token.line,
+                    column: token.column,
+                }),
+            })
+            .collect()
+    }
+}
+
+impl<'a, T> Tokens<'a, T> {
+    //
+    //      PRIVATE
+    //
+    fn longest_match(&self, position: usize) -> Option<(T, usize)> {
+        let rest = &self.input[position..];
+        let valid_len = |result: &MatchRuleResult<(T, usize)>| match result {
+            MatchRuleResult::Match((_, len)) if *len > 0 && rest.is_char_boundary(*len) => *len,
+            _ => 0,
+        };
+
+        let best = self.lexer.rules.best_match(rest, valid_len);
+        let len = valid_len(&best);
+        best.into_inner()
+            .filter(|_| len > 0)
+            .map(|(value, _)| (value, len))
+    }
+
+    fn advance(&mut self, len: usize) {
+        for c in self.input[self.position..self.position + len].chars() {
+            if c == '\n' {
+                self.line += 1;
+                self.column = 1;
+            } else {
+                self.column += 1;
+            }
+        }
+        self.position += len;
+    }
+
+    fn unmatched_len(&self) -> usize {
+        let mut chars = self.input[self.position..].char_indices().skip(1);
+        chars
+            .find(|&(i, _)| self.longest_match(self.position + i).is_some())
+            .map_or(self.input.len() - self.position, |(i, _)| i)
+    }
+}
+//
+//      ITERATOR IMPLS
+//
+impl<'a, T> Iterator for Tokens<'a, T> {
+    type Item = Token<'a, T>;
+
+    fn next(&mut self) -> Option<Token<'a, T>> {
+        while self.position < self.input.len() {
+            let (start, line, column) = (self.position, self.line, self.column);
+            let value = match self.longest_match(start) {
+                Some((value, len)) => {
+                    self.advance(len);
+                    Some(value)
+                }
+                None => {
+                    self.advance(self.unmatched_len());
+                    None
+                }
+            };
+
+            let skipped = match (&value, &self.lexer.skip) {
+                (Some(value), Some(skip)) => skip(value),
+                _ => false,
+            };
+            if !skipped {
+                return Some(Token {
+                    value,
+                    text: &self.input[start..self.position],
+                    span: start..self.position,
+                    line,
+                    column,
+                });
+            }
+        }
+        None
+    }
+}
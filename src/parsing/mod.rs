@@ -1,4 +1,10 @@
+mod expression;
+mod lexer;
 pub mod rules;
+mod sexpr;
 mod splitting;
 
-pub use splitting::Splitter;
+pub use expression::{Associativity, Evaluator, Expr, ExprError, ExprToken};
+pub use lexer::{LexError, Lexer, Token, Tokens};
+pub use sexpr::{EvalError, ReadError, ReplBuffer, SExpr, SExprEvaluator};
+pub use splitting::{DisplaySplit, Split, Splitter};
@@ -0,0 +1,173 @@
+use crate::parsing::rules::{
+    MatchRule, MatchRuleResult, Optional, StrSlice, TypeConstructor, Unit,
+};
+//
+//      PRIVATE
+//
+/// Labels combined rules from their parts, so a composed rule still reads in debug output.
+fn combined_label(parts: &[Option<&str>], join: &str) -> Option<String> {
+    let parts: Option<Vec<&str>> = parts.iter().copied().collect();
+    parts.map(|parts| parts.join(join))
+}
+
+fn wrapped_label(label: Option<&str>, prefix: &str, suffix: &str) -> Option<String> {
+    label.map(|label| format!("{prefix}{label}{suffix}"))
+}
+
+/// How much of `input` a match used, or `None` if the rule matched something other than a
+/// prefix of it. Empty matches use nothing wherever they point.
+fn prefix_len(input: &str, matched: &str) -> Option<usize> {
+    let is_prefix = matched.as_ptr() == input.as_ptr() && matched.len() <= input.len();
+    (is_prefix || matched.is_empty()).then_some(matched.len())
+}
+//
+//      GENERIC COMBINATORS
+//
+/// Combined rules keep the priority of `self`.
+impl<A: ?Sized + 'static, R: TypeConstructor + 'static> MatchRule<A, R> {
+    /// Matches with `self`, or with `other` when `self` doesn't match.
+    pub fn or(self, other: MatchRule<A, R>) -> MatchRule<A, R> {
+        let label = combined_label(&[self.label(), other.label()], " | ");
+        MatchRule {
+            label,
+            priority: self.priority,
+            rule: Box::new(move |item| match (self.rule)(item) {
+                MatchRuleResult::NoMatch => (other.rule)(item),
+                matched => matched,
+            }),
+        }
+    }
+
+    /// Feeds the tested item and the matched output to `f`, which can still reject them.
+    pub fn and_then<R2: TypeConstructor, F>(self, f: F) -> MatchRule<A, R2>
+    where
+        F: for<'a> Fn(&'a A, R::Of<'a>) -> MatchRuleResult<R2::Of<'a>> + 'static,
+    {
+        MatchRule {
+            label: self.label.clone(),
+            priority: self.priority,
+            rule: Box::new(move |item| match (self.rule)(item) {
+                MatchRuleResult::Match(output) => f(item, output),
+                MatchRuleResult::NoMatch => MatchRuleResult::NoMatch,
+            }),
+        }
+    }
+
+    /// As `and_then`, for an `f` that always accepts.
+    pub fn map<R2: TypeConstructor, F>(self, f: F) -> MatchRule<A, R2>
+    where
+        F: for<'a> Fn(&'a A, R::Of<'a>) -> R2::Of<'a> + 'static,
+    {
+        MatchRule {
+            label: self.label.clone(),
+            priority: self.priority,
+            rule: Box::new(move |item| (self.rule)(item).map(|output| f(item, output))),
+        }
+    }
+
+    /// Rejects matches whose output fails `predicate`.
+    pub fn filter<F>(self, predicate: F) -> MatchRule<A, R>
+    where
+        F: for<'a, 'b> Fn(&'b R::Of<'a>) -> bool + 'static,
+    {
+        MatchRule {
+            label: self.label.clone(),
+            priority: self.priority,
+            rule: Box::new(move |item| match (self.rule)(item) {
+                MatchRuleResult::Match(output) if !predicate(&output) => MatchRuleResult::NoMatch,
+                result => result,
+            }),
+        }
+    }
+
+    /// Always matches, with `None` where `self` doesn't.
+    pub fn optional(self) -> MatchRule<A, Optional<R>> {
+        MatchRule {
+            label: wrapped_label(self.label(), "", "?"),
+            priority: self.priority,
+            rule: Box::new(move |item| MatchRuleResult::Match((self.rule)(item).into_inner())),
+        }
+    }
+}
+/// Matches exactly when the inner rule doesn't.
+impl<A: ?Sized + 'static, R: TypeConstructor + 'static> std::ops::Not for MatchRule<A, R> {
+    type Output = MatchRule<A, Unit>;
+
+    fn not(self) -> MatchRule<A, Unit> {
+        MatchRule {
+            label: wrapped_label(self.label(), "!", ""),
+            priority: self.priority,
+            rule: Box::new(move |item| match (self.rule)(item) {
+                MatchRuleResult::Match(_) => MatchRuleResult::NoMatch,
+                MatchRuleResult::NoMatch => MatchRuleResult::Match(()),
+            }),
+        }
+    }
+}
+//
+//      STR COMBINATORS
+//
+/// Repetition and sequencing need to know how much input a match used up, so they are only
+/// available for `str` rules matching a prefix of their input. They match the whole prefix
+/// covered by their parts, and a part matching anywhere else counts as not matching.
+impl MatchRule<str, StrSlice> {
+    /// Matches `self` as many times as it will in a row, including none. Stops at an empty
+    /// match rather than looping on it.
+    pub fn many(self) -> MatchRule<str, StrSlice> {
+        MatchRule {
+            label: wrapped_label(self.label(), "", "*"),
+            priority: self.priority,
+            rule: Box::new(move |input| {
+                let mut used = 0;
+                while let MatchRuleResult::Match(matched) = (self.rule)(&input[used..]) {
+                    match prefix_len(&input[used..], matched) {
+                        Some(len) if len > 0 => used += len,
+                        _ => break,
+                    }
+                }
+                MatchRuleResult::Match(&input[..used])
+            }),
+        }
+    }
+
+    /// As `many`, but needs at least one match.
+    pub fn many1(self) -> MatchRule<str, StrSlice> {
+        let label = wrapped_label(self.label(), "", "+");
+        let priority = self.priority;
+        let many = self.many();
+        MatchRule {
+            label,
+            priority,
+            rule: Box::new(move |input| match (many.rule)(input) {
+                MatchRuleResult::Match(matched) if !matched.is_empty() => {
+                    MatchRuleResult::Match(matched)
+                }
+                _ => MatchRuleResult::NoMatch,
+            }),
+        }
+    }
+
+    /// Matches each rule in turn, each starting where the last one stopped. Takes the
+    /// priority of the first rule.
+    pub fn sequence(rules: Vec<MatchRule<str, StrSlice>>) -> MatchRule<str, StrSlice> {
+        let labels: Vec<Option<&str>> = rules.iter().map(MatchRule::label).collect();
+        MatchRule {
+            label: combined_label(&labels, " "),
+            priority: rules.first().map_or(0, |rule| rule.priority),
+            rule: Box::new(move |input| {
+                let mut used = 0;
+                for rule in &rules {
+                    let rest = &input[used..];
+                    match (rule.rule)(rest) {
+                        MatchRuleResult::Match(matched) => match prefix_len(rest, matched) {
+                            Some(len) => used += len,
+                            None => return MatchRuleResult::NoMatch,
+                        },
+                        MatchRuleResult::NoMatch => return MatchRuleResult::NoMatch,
+                    }
+                }
+                MatchRuleResult::Match(&input[..used])
+            }),
+        }
+    }
+}
@@ -1,15 +1,21 @@
-use crate::parsing::rules::{Rule, RuleSet, TypeConstructor};
+use crate::parsing::rules::{Rule, RuleResult, RuleSet, TypeConstructor};
 //
 // STRUCTS
 //
+#[derive(Default)]
 pub enum MatchRuleResult<A> {
     Match(A),
+    #[default]
     NoMatch,
 }
 
+type MatchFn<Item, TC> =
+    Box<dyn for<'a> Fn(&'a Item) -> MatchRuleResult<<TC as TypeConstructor>::Of<'a>>>;
+
 pub struct MatchRule<Item: ?Sized, TC: TypeConstructor> {
-    pub rule: for<'a> fn(&'a Item) -> MatchRuleResult<TC::Of<'a>>,
+    pub rule: MatchFn<Item, TC>,
     pub priority: usize,
+    pub label: Option<String>,
 }
 
 pub struct MatchRuleSet<Item: ?Sized, TC: TypeConstructor> {
@@ -49,6 +55,12 @@ impl<A> MatchRuleResult<A> {
     }
 }
 
+impl<A> RuleResult for MatchRuleResult<A> {
+    fn is_match(&self) -> bool {
+        matches!(self, MatchRuleResult::Match(_))
+    }
+}
+
 impl<A> From<MatchRuleResult<A>> for Option<A> {
     fn from(result: MatchRuleResult<A>) -> Self {
         result.into_inner()
@@ -64,12 +76,6 @@ impl<A> From<Option<A>> for MatchRuleResult<A> {
     }
 }
 
-impl<A> Default for MatchRuleResult<A> {
-    fn default() -> Self {
-        MatchRuleResult::NoMatch
-    }
-}
-
 //
 // IMPL RULE
 //
@@ -83,6 +89,14 @@ impl<A: ?Sized, R: TypeConstructor> Rule for MatchRule<A, R> {
     fn test<'a>(&'a self, eval: &'a A) -> MatchRuleResult<R::Of<'a>> {
         (self.rule)(eval)
     }
+
+    fn priority(&self) -> usize {
+        self.priority
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
 }
 
 //
@@ -121,12 +135,39 @@ impl<A: ?Sized, R: TypeConstructor> Default for MatchRuleSet<A, R> {
 // IMPL METHODS
 //
 impl<A: ?Sized, R: TypeConstructor> MatchRule<A, R> {
-    pub fn new(rule: for<'a> fn(&'a A) -> MatchRuleResult<R::Of<'a>>, priority: usize) -> Self {
-        MatchRule { rule, priority }
+    /// Takes any function or closure, so rules can capture their configuration.
+    pub fn new<F>(rule: F, priority: usize) -> Self
+    where
+        F: for<'a> Fn(&'a A) -> MatchRuleResult<R::Of<'a>> + 'static,
+    {
+        MatchRule {
+            rule: Box::new(rule),
+            priority,
+            label: None,
+        }
+    }
+
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    pub fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+}
+
+impl<A: ?Sized, R: TypeConstructor> std::fmt::Debug for MatchRule<A, R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("MatchRule")
+            .field("label", &self.label)
+            .field("priority", &self.priority)
+            .finish_non_exhaustive()
     }
 }
 
 impl<A: ?Sized, R: TypeConstructor> MatchRuleSet<A, R> {
+    /// Stable, so rules of equal priority keep their insertion order.
     fn priority_sort(&mut self) {
         self.match_rules
             .sort_by_key(|rule| std::cmp::Reverse(rule.priority));
@@ -1,9 +1,14 @@
+mod combinators;
 mod match_rules;
+mod rule_file;
 mod rule_map;
+mod rule_trace;
 mod rule_traits;
 mod type_contstructor;
 
 pub use match_rules::{MatchRule, MatchRuleResult, MatchRuleSet};
-pub use rule_map::{ArgRule, RuleMap};
-pub use rule_traits::{Rule, RuleSet};
-pub use type_contstructor::TypeConstructor;
+pub use rule_file::{PatternKind, RuleFileError, RuleSpec, TaggedMatch};
+pub use rule_map::{ArgRule, Arity, RuleMap, RuleMapError};
+pub use rule_trace::{RuleTrace, TraceEntry};
+pub use rule_traits::{Rule, RuleResult, RuleSet};
+pub use type_contstructor::{Lexeme, Optional, StrSlice, Tagged, TypeConstructor, Unit};
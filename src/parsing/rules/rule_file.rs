@@ -0,0 +1,608 @@
+use std::{iter::Peekable, rc::Rc, str::CharIndices, str::FromStr};
+
+use thiserror::Error;
+
+use crate::parsing::rules::{MatchRule, MatchRuleResult, MatchRuleSet, RuleSet, Tagged};
+//
+//      ERRORS
+//
+/// Lines are 1-based.
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum RuleFileError {
+    #[error("Line {line}: missing {field}.")]
+    MissingFieldError { line: usize, field: &'static str },
+    #[error("Line {line}: invalid priority {found:?}.")]
+    PriorityError { line: usize, found: String },
+    #[error("Line {line}: unknown pattern kind {found:?}.")]
+    UnknownKindError { line: usize, found: String },
+    #[error("Line {line}: {message}.")]
+    PatternError { line: usize, message: String },
+}
+//
+//      STRUCTS
+//
+/// The output of a loaded rule: its tag and the part of the input its pattern matched.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TaggedMatch<'a> {
+    pub tag: Rc<str>,
+    pub text: &'a str,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PatternKind {
+    Literal,
+    Prefix,
+    Suffix,
+    Class,
+    Regex,
+}
+
+/// One rule of a rule file, in the form `<tag> <priority> <kind> <pattern>`.
+///
+/// `literal`, `prefix`, `suffix` and `regex` patterns are double-quoted, with `\"`, `\\`, `\n`
+/// and `\t` escapes. Other escapes are kept as written, for regex patterns. `class` patterns
+/// are a bracketed class like `[a-z_]` that every character of the input must be in. `regex`
+/// patterns support `.`, classes, `\d`, `\w`, `\s`, groups, `|` and the `*`, `+` and `?`
+/// quantifiers, and must match the whole input. Blank lines and lines starting with `#` are
+/// ignored.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RuleSpec {
+    pub tag: Rc<str>,
+    pub priority: usize,
+    pub kind: PatternKind,
+    /// The pattern as written, without quotes or escapes.
+    pub pattern: String,
+    pub line: usize,
+    matcher: Matcher,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Matcher {
+    Text(String),
+    Class(CharClass),
+    Regex(Program),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct CharClass {
+    negated: bool,
+    items: Vec<ClassItem>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum ClassItem {
+    Range(char, char),
+    Digit,
+    Word,
+    Space,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Node {
+    Char(char),
+    Any,
+    Class(CharClass),
+    Group(Vec<Vec<Piece>>),
+}
+
+/// `min` is 0 or 1 and `max` is 1 or unbounded, from the three quantifiers.
+#[derive(Clone, Debug, PartialEq)]
+struct Piece {
+    node: Node,
+    min: usize,
+    max: Option<usize>,
+}
+
+/// A regex compiled to a Thompson NFA, starting at the first instruction.
+#[derive(Clone, Debug, PartialEq)]
+struct Program {
+    insts: Vec<Inst>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Inst {
+    Char(char),
+    Any,
+    Class(CharClass),
+    /// Continues at both instructions without reading.
+    Split(usize, usize),
+    Jump(usize),
+    Match,
+}
+
+struct PatternParser<'a> {
+    chars: Peekable<CharIndices<'a>>,
+}
+//
+//      STRUCT IMPLS
+//
+impl RuleSpec {
+    /// Parses every rule in a rule file, stopping at the first invalid line.
+    pub fn parse_all(text: &str) -> Result<Vec<RuleSpec>, RuleFileError> {
+        text.lines()
+            .enumerate()
+            .filter(|(_, content)| {
+                let content = content.trim();
+                !content.is_empty() && !content.starts_with('#')
+            })
+            .map(|(i, content)| RuleSpec::parse_line(content, i + 1))
+            .collect()
+    }
+
+    /// Parses one rule, reporting errors against `line`.
+    pub fn parse_line(content: &str, line: usize) -> Result<RuleSpec, RuleFileError> {
+        let mut rest = content.trim();
+        let mut field = |name| {
+            let (value, tail) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+            rest = tail.trim_start();
+            match value.is_empty() {
+                true => Err(RuleFileError::MissingFieldError { line, field: name }),
+                false => Ok(value),
+            }
+        };
+
+        let tag = field("tag")?;
+        let priority = field("priority")?;
+        let kind = field("pattern kind")?;
+        let priority = priority.parse().map_err(|_| RuleFileError::PriorityError {
+            line,
+            found: priority.to_string(),
+        })?;
+        let kind = match kind {
+            "literal" => PatternKind::Literal,
+            "prefix" => PatternKind::Prefix,
+            "suffix" => PatternKind::Suffix,
+            "class" => PatternKind::Class,
+            "regex" => PatternKind::Regex,
+            found => {
+                return Err(RuleFileError::UnknownKindError {
+                    line,
+                    found: found.to_string(),
+                });
+            }
+        };
+        if rest.is_empty() {
+            return Err(RuleFileError::MissingFieldError {
+                line,
+                field: "pattern",
+            });
+        }
+
+        let pattern_error = |message| RuleFileError::PatternError { line, message };
+        let (pattern, matcher) = match kind {
+            PatternKind::Class => {
+                let class = parse_class_pattern(rest).map_err(pattern_error)?;
+                (rest.to_string(), Matcher::Class(class))
+            }
+            PatternKind::Regex => {
+                let pattern = unquote(rest).map_err(pattern_error)?;
+                let regex = PatternParser::new(&pattern)
+                    .parse_regex()
+                    .map_err(pattern_error)?;
+                (pattern, Matcher::Regex(Program::compile(&regex)))
+            }
+            _ => {
+                let pattern = unquote(rest).map_err(pattern_error)?;
+                (pattern.clone(), Matcher::Text(pattern))
+            }
+        };
+
+        Ok(RuleSpec {
+            tag: tag.into(),
+            priority,
+            kind,
+            pattern,
+            line,
+            matcher,
+        })
+    }
+
+    /// The part of `input` the pattern matches, if it matches.
+    pub fn test<'a>(&self, input: &'a str) -> Option<&'a str> {
+        match (&self.matcher, self.kind) {
+            (Matcher::Text(text), PatternKind::Prefix) => input
+                .starts_with(text.as_str())
+                .then(|| &input[..text.len()]),
+            (Matcher::Text(text), PatternKind::Suffix) => input
+                .ends_with(text.as_str())
+                .then(|| &input[input.len() - text.len()..]),
+            (Matcher::Text(text), _) => (input == text).then_some(input),
+            (Matcher::Class(class), _) => {
+                let all = !input.is_empty() && input.chars().all(|c| class.contains(c));
+                all.then_some(input)
+            }
+            (Matcher::Regex(program), _) => program.matches(input).then_some(input),
+        }
+    }
+
+    /// A rule labelled with its tag and line, so traces point back into the file.
+    pub fn into_rule(self) -> MatchRule<str, Tagged> {
+        let label = format!("{} (line {})", self.tag, self.line);
+        let priority = self.priority;
+        MatchRule::new(
+            move |input: &str| {
+                self.test(input)
+                    .map(|text| TaggedMatch {
+                        tag: self.tag.clone(),
+                        text,
+                    })
+                    .into()
+            },
+            priority,
+        )
+        .with_label(label)
+    }
+}
+
+impl CharClass {
+    fn contains(&self, c: char) -> bool {
+        let found = self.items.iter().any(|item| match *item {
+            ClassItem::Range(lo, hi) => (lo..=hi).contains(&c),
+            ClassItem::Digit => c.is_ascii_digit(),
+            ClassItem::Word => c.is_alphanumeric() || c == '_',
+            ClassItem::Space => c.is_whitespace(),
+        });
+        found != self.negated
+    }
+}
+
+impl Program {
+    fn compile(alternatives: &[Vec<Piece>]) -> Self {
+        let mut insts = vec![];
+        compile_alternatives(&mut insts, alternatives);
+        insts.push(Inst::Match);
+        Program { insts }
+    }
+
+    /// Whether the pattern matches all of `text`. Every live state steps through each
+    /// character together, so this takes time linear in both the pattern and the text.
+    fn matches(&self, text: &str) -> bool {
+        let mut seen = vec![usize::MAX; self.insts.len()];
+        let mut current = vec![];
+        let mut next = vec![];
+        self.add_state(&mut current, &mut seen, 0, 0);
+
+        for (step, c) in text.chars().enumerate() {
+            for &pc in &current {
+                let read = match &self.insts[pc] {
+                    Inst::Char(expected) => c == *expected,
+                    Inst::Any => true,
+                    Inst::Class(class) => class.contains(c),
+                    _ => false,
+                };
+                if read {
+                    self.add_state(&mut next, &mut seen, pc + 1, step + 1);
+                }
+            }
+            std::mem::swap(&mut current, &mut next);
+            next.clear();
+            if current.is_empty() {
+                return false;
+            }
+        }
+        current
+            .iter()
+            .any(|&pc| matches!(self.insts[pc], Inst::Match))
+    }
+
+    /// Adds `pc` and everything reachable from it without reading, keeping the states that
+    /// read or match. `seen` marks states already added at `step`, which also stops empty
+    /// loops like `(a*)*` from cycling.
+    fn add_state(&self, states: &mut Vec<usize>, seen: &mut [usize], pc: usize, step: usize) {
+        let mut stack = vec![pc];
+        while let Some(pc) = stack.pop() {
+            if seen[pc] == step {
+                continue;
+            }
+            seen[pc] = step;
+            match self.insts[pc] {
+                Inst::Jump(to) => stack.push(to),
+                Inst::Split(first, second) => stack.extend([second, first]),
+                _ => states.push(pc),
+            }
+        }
+    }
+}
+
+impl<'a> PatternParser<'a> {
+    fn new(pattern: &'a str) -> Self {
+        Self {
+            chars: pattern.char_indices().peekable(),
+        }
+    }
+    //
+    //      PRIVATE
+    //
+    fn peek(&mut self) -> Option<char> {
+        self.chars.peek().map(|&(_, c)| c)
+    }
+
+    fn next_or(&mut self, message: &str) -> Result<(usize, char), String> {
+        self.chars.next().ok_or_else(|| message.to_string())
+    }
+
+    fn escape(&mut self) -> Result<ClassItem, String> {
+        let (_, c) = self.next_or("pattern ends in an unfinished escape")?;
+        Ok(match c {
+            'd' => ClassItem::Digit,
+            'w' => ClassItem::Word,
+            's' => ClassItem::Space,
+            'n' => ClassItem::Range('\n', '\n'),
+            't' => ClassItem::Range('\t', '\t'),
+            c => ClassItem::Range(c, c),
+        })
+    }
+    //
+    //      GRAMMAR
+    //
+    fn parse_regex(mut self) -> Result<Vec<Vec<Piece>>, String> {
+        let alternatives = self.parse_alternatives()?;
+        match self.chars.next() {
+            Some((i, c)) => Err(format!("unexpected {c:?} at byte {i} of the pattern")),
+            None => Ok(alternatives),
+        }
+    }
+
+    fn parse_alternatives(&mut self) -> Result<Vec<Vec<Piece>>, String> {
+        let mut alternatives = vec![self.parse_sequence()?];
+        while self.peek() == Some('|') {
+            self.chars.next();
+            alternatives.push(self.parse_sequence()?);
+        }
+        Ok(alternatives)
+    }
+
+    fn parse_sequence(&mut self) -> Result<Vec<Piece>, String> {
+        let mut pieces = vec![];
+        while let Some(c) = self.peek()
+            && c != '|'
+            && c != ')'
+        {
+            let node = self.parse_node()?;
+            let (min, max) = match self.peek() {
+                Some('*') => (0, None),
+                Some('+') => (1, None),
+                Some('?') => (0, Some(1)),
+                _ => {
+                    pieces.push(Piece {
+                        node,
+                        min: 1,
+                        max: Some(1),
+                    });
+                    continue;
+                }
+            };
+            self.chars.next();
+            pieces.push(Piece { node, min, max });
+        }
+        Ok(pieces)
+    }
+
+    fn parse_node(&mut self) -> Result<Node, String> {
+        let (i, c) = self.next_or("pattern ends early")?;
+        Ok(match c {
+            '(' => {
+                let group = self.parse_alternatives()?;
+                match self.chars.next() {
+                    Some((_, ')')) => Node::Group(group),
+                    _ => return Err(format!("group at byte {i} of the pattern is never closed")),
+                }
+            }
+            '[' => Node::Class(self.parse_class()?),
+            '.' => Node::Any,
+            '\\' => match self.escape()? {
+                ClassItem::Range(c, _) => Node::Char(c),
+                item => Node::Class(CharClass {
+                    negated: false,
+                    items: vec![item],
+                }),
+            },
+            '*' | '+' | '?' => return Err(format!("nothing to repeat at byte {i} of the pattern")),
+            c => Node::Char(c),
+        })
+    }
+
+    /// Parses a class after its opening `[`.
+    fn parse_class(&mut self) -> Result<CharClass, String> {
+        let negated = self.peek() == Some('^');
+        if negated {
+            self.chars.next();
+        }
+
+        let mut items = vec![];
+        loop {
+            let (_, c) = self.next_or("character class is never closed")?;
+            let lo = match c {
+                ']' => return Ok(CharClass { negated, items }),
+                '\\' => match self.escape()? {
+                    ClassItem::Range(c, _) => c,
+                    item => {
+                        items.push(item);
+                        continue;
+                    }
+                },
+                c => c,
+            };
+
+            let mut ahead = self.chars.clone();
+            let is_range = matches!(ahead.next(), Some((_, '-')))
+                && ahead.next().is_some_and(|(_, c)| c != ']');
+            if !is_range {
+                items.push(ClassItem::Range(lo, lo));
+                continue;
+            }
+
+            self.chars.next();
+            let hi = match self.next_or("character class is never closed")? {
+                (_, '\\') => match self.escape()? {
+                    ClassItem::Range(c, _) => c,
+                    _ => return Err("a class shorthand can't end a range".to_string()),
+                },
+                (_, c) => c,
+            };
+            if lo > hi {
+                return Err(format!("range {lo:?}-{hi:?} is backwards"));
+            }
+            items.push(ClassItem::Range(lo, hi));
+        }
+    }
+}
+//
+//      PRIVATE
+//
+fn unquote(pattern: &str) -> Result<String, String> {
+    let inner = pattern
+        .strip_prefix('"')
+        .and_then(|rest| rest.strip_suffix('"'))
+        .ok_or_else(|| format!("pattern {pattern:?} should be double-quoted"))?;
+
+    let mut text = String::new();
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('n') => text.push('\n'),
+                Some('t') => text.push('\t'),
+                Some(c @ ('"' | '\\')) => text.push(c),
+                // regex escapes are kept for the regex parser
+                Some(c) => {
+                    text.push('\\');
+                    text.push(c);
+                }
+                None => return Err("pattern ends in an unfinished escape".to_string()),
+            },
+            '"' => return Err("quotes inside a pattern must be escaped".to_string()),
+            c => text.push(c),
+        }
+    }
+    Ok(text)
+}
+
+fn parse_class_pattern(pattern: &str) -> Result<CharClass, String> {
+    let mut parser = PatternParser::new(pattern);
+    if parser.chars.next().map(|(_, c)| c) != Some('[') {
+        return Err(format!("class {pattern:?} should start with '['"));
+    }
+    let class = parser.parse_class()?;
+    match parser.chars.next() {
+        Some((i, c)) => Err(format!("unexpected {c:?} at byte {i} of the pattern")),
+        None => Ok(class),
+    }
+}
+
+fn compile_alternatives(insts: &mut Vec<Inst>, alternatives: &[Vec<Piece>]) {
+    let mut jumps = vec![];
+    for (i, sequence) in alternatives.iter().enumerate() {
+        let last = i + 1 == alternatives.len();
+        let split = insts.len();
+        if !last {
+            insts.push(Inst::Jump(0));
+        }
+        for piece in sequence {
+            compile_piece(insts, piece);
+        }
+        if !last {
+            jumps.push(insts.len());
+            insts.push(Inst::Jump(0));
+            insts[split] = Inst::Split(split + 1, insts.len());
+        }
+    }
+    for jump in jumps {
+        insts[jump] = Inst::Jump(insts.len());
+    }
+}
+
+/// Placeholder jumps are patched once the instructions they skip are known.
+fn compile_piece(insts: &mut Vec<Inst>, piece: &Piece) {
+    let optional = piece.min == 0;
+    let start = insts.len();
+    if optional {
+        insts.push(Inst::Jump(0));
+    }
+    let body = insts.len();
+    match &piece.node {
+        Node::Char(c) => insts.push(Inst::Char(*c)),
+        Node::Any => insts.push(Inst::Any),
+        Node::Class(class) => insts.push(Inst::Class(class.clone())),
+        Node::Group(alternatives) => compile_alternatives(insts, alternatives),
+    }
+    if piece.max.is_none() {
+        insts.push(Inst::Split(body, insts.len() + 1));
+    }
+    if optional {
+        insts[start] = Inst::Split(body, insts.len());
+    }
+}
+//
+//      TRAIT IMPLS
+//
+/// Loads a rule file, inserting its rules in order.
+impl FromStr for MatchRuleSet<str, Tagged> {
+    type Err = RuleFileError;
+
+    fn from_str(text: &str) -> Result<Self, RuleFileError> {
+        let mut set = MatchRuleSet::default();
+        for spec in RuleSpec::parse_all(text)? {
+            set.insert(spec.into_rule());
+        }
+        Ok(set)
+    }
+}
+//
+//      TESTS
+//
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn regex(pattern: &str) -> RuleSpec {
+        RuleSpec::parse_line(&format!("t 0 regex \"{pattern}\""), 1).unwrap()
+    }
+
+    fn matches(pattern: &str, input: &str) -> bool {
+        regex(pattern).test(input).is_some()
+    }
+
+    #[test]
+    fn regex_matches_the_whole_input() {
+        assert!(matches("ab|cd", "cd"));
+        assert!(!matches("ab|cd", "abcd"));
+        assert!(matches("a(b|c)*d", "abcbd"));
+        assert!(matches("a(b|c)*d", "ad"));
+        assert!(!matches("a(b|c)*d", "abed"));
+        assert!(matches("[a-z_]\\w*", "snake_case2"));
+        assert!(!matches("[a-z_]\\w*", "2snake"));
+        assert!(matches("\\d+(\\.\\d+)?", "3.14"));
+        assert!(!matches("\\d+(\\.\\d+)?", "3."));
+        assert!(matches("x?y+", "yy"));
+        assert!(!matches("x?y+", "x"));
+        assert!(matches("é.中", "é😀中"));
+        assert!(matches("", ""));
+        assert!(!matches("", "a"));
+        assert!(matches("a|", ""));
+    }
+
+    #[test]
+    fn nested_quantifiers_terminate() {
+        assert!(matches("(a*)*", ""));
+        assert!(matches("(a*)*b", "aaab"));
+        assert!(matches("(a?)+b", "b"));
+        assert!(matches("((a|b)*)+c", "ababc"));
+        assert!(!matches("(a|aa)*c", "aaaa"));
+
+        let input = "a".repeat(5000);
+        for pattern in ["(a*)*b", "(a+)+b", "(a|aa)*b", "(a?)*a*b", "((a*)*)*b"] {
+            assert!(!matches(pattern, &input), "{pattern}");
+        }
+        assert!(matches("(a|aa)*(a*)*", &input));
+    }
+
+    #[test]
+    fn long_inputs_do_not_recurse() {
+        let input = "x".repeat(200_000);
+        assert!(matches(".*", &input));
+        assert!(matches("(x|y)+", &input));
+        assert!(!matches(".*y", &input));
+        assert_eq!(regex(".*").test(&input), Some(input.as_str()));
+    }
+}
@@ -1,28 +1,173 @@
 use crate::parsing::rules::Rule;
 use std::borrow::Borrow;
 use std::collections::HashMap;
+use std::fmt::Display;
 use std::hash::Hash;
+use thiserror::Error;
+//
+//      ERRORS
+//
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum RuleMapError {
+    #[error("No rule for this item.")]
+    UnknownRule,
+    #[error("Expected {expected} arguments, got {got}.")]
+    ArityMismatch { expected: Arity, got: usize },
+    #[error("{0}")]
+    RuleFailed(String),
+}
+//
+//      STRUCTS
+//
+type ArgFn<Item, Result> = Box<dyn Fn(&[Item]) -> std::result::Result<Result, RuleMapError>>;
+
+/// How many arguments a rule takes. `Range` bounds are inclusive.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Arity {
+    Exact(usize),
+    Min(usize),
+    Range(usize, usize),
+    Variadic,
+}
 
 pub struct ArgRule<Item, Result> {
-    rule: Box<dyn Fn(&[Item]) -> Result>,
+    rule: ArgFn<Item, Result>,
+    arity: Arity,
+}
+
+/// Rules looked up by `B`, taking arguments of type `A`, which is `B` unless given.
+pub struct RuleMap<B, R, A = B> {
+    map: HashMap<B, ArgRule<A, R>>,
+}
+//
+//      STRUCT IMPLS
+//
+impl Arity {
+    pub fn accepts(&self, count: usize) -> bool {
+        match *self {
+            Arity::Exact(n) => count == n,
+            Arity::Min(min) => count >= min,
+            Arity::Range(min, max) => (min..=max).contains(&count),
+            Arity::Variadic => true,
+        }
+    }
+}
+
+impl Display for Arity {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Arity::Exact(n) => write!(f, "{n}"),
+            Arity::Min(min) => write!(f, "at least {min}"),
+            Arity::Range(min, max) => write!(f, "{min} to {max}"),
+            Arity::Variadic => write!(f, "any number of"),
+        }
+    }
 }
 
-pub struct RuleMap<B, R> {
-    map: HashMap<B, ArgRule<B, R>>,
+impl<A, R> ArgRule<A, R> {
+    pub fn arity(&self) -> Arity {
+        self.arity
+    }
+
+    /// Checks the arity before calling the rule.
+    pub fn evaluate(&self, args: &[A]) -> Result<R, RuleMapError> {
+        if !self.arity.accepts(args.len()) {
+            return Err(RuleMapError::ArityMismatch {
+                expected: self.arity,
+                got: args.len(),
+            });
+        }
+        (self.rule)(args)
+    }
 }
 
-impl<A: std::cmp::Eq + Hash, R> RuleMap<A, R> {
+impl<B: std::cmp::Eq + Hash, R, A> RuleMap<B, R, A> {
     pub fn new() -> Self {
         Self {
             map: HashMap::new(),
         }
     }
 
-    pub fn insert(&mut self, item: A, rule: Box<dyn Fn(&[A]) -> R>) {
-        self.map.insert(item, ArgRule { rule });
+    /// Replaces any rule already under `item`.
+    pub fn insert(&mut self, item: B, arity: Arity, rule: impl Fn(&[A]) -> R + 'static) {
+        self.insert_fallible(item, arity, move |args| Ok(rule(args)));
+    }
+
+    /// As `insert`, for rules that can fail. They usually fail with `RuleFailed`.
+    pub fn insert_fallible(
+        &mut self,
+        item: B,
+        arity: Arity,
+        rule: impl Fn(&[A]) -> Result<R, RuleMapError> + 'static,
+    ) {
+        let rule = Box::new(rule);
+        self.map.insert(item, ArgRule { rule, arity });
     }
 
-    pub fn evaluate(&self, item: A, args: &[A]) -> Option<R> {
-        Some((self.map.get(&item)?.rule)(args))
+    pub fn evaluate<Q>(&self, item: &Q, args: &[A]) -> Result<R, RuleMapError>
+    where
+        B: Borrow<Q>,
+        Q: std::cmp::Eq + Hash + ?Sized,
+    {
+        self.get(item)
+            .ok_or(RuleMapError::UnknownRule)?
+            .evaluate(args)
+    }
+
+    pub fn get<Q>(&self, item: &Q) -> Option<&ArgRule<A, R>>
+    where
+        B: Borrow<Q>,
+        Q: std::cmp::Eq + Hash + ?Sized,
+    {
+        self.map.get(item)
+    }
+
+    pub fn contains<Q>(&self, item: &Q) -> bool
+    where
+        B: Borrow<Q>,
+        Q: std::cmp::Eq + Hash + ?Sized,
+    {
+        self.map.contains_key(item)
+    }
+
+    pub fn remove<Q>(&mut self, item: &Q) -> Option<ArgRule<A, R>>
+    where
+        B: Borrow<Q>,
+        Q: std::cmp::Eq + Hash + ?Sized,
+    {
+        self.map.remove(item)
+    }
+
+    /// In no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (&B, &ArgRule<A, R>)> {
+        self.map.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+}
+//
+//      TRAIT IMPLS
+//
+impl<A, R> Rule for ArgRule<A, R> {
+    type Item = [A];
+    type Result<'a>
+        = Result<R, RuleMapError>
+    where
+        Self: 'a;
+
+    fn test<'a>(&'a self, eval: &'a [A]) -> Result<R, RuleMapError> {
+        self.evaluate(eval)
+    }
+}
+
+impl<B: std::cmp::Eq + Hash, R, A> Default for RuleMap<B, R, A> {
+    fn default() -> Self {
+        Self::new()
     }
 }
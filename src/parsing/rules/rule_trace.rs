@@ -0,0 +1,82 @@
+use std::{fmt::Display, time::Duration};
+//
+//      STRUCTS
+//
+/// What happened when one rule was tested.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TraceEntry {
+    /// The rule's index in its set.
+    pub index: usize,
+    pub label: Option<String>,
+    pub priority: usize,
+    pub matched: bool,
+    pub elapsed: Duration,
+}
+
+/// The rules a traced evaluation tested, in the order it tested them. Rules skipped by a
+/// short-circuit don't appear. Displays as a table.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RuleTrace {
+    pub entries: Vec<TraceEntry>,
+}
+//
+//      STRUCT IMPLS
+//
+impl RuleTrace {
+    pub fn push(&mut self, entry: TraceEntry) {
+        self.entries.push(entry);
+    }
+
+    pub fn total_elapsed(&self) -> Duration {
+        self.entries.iter().map(|entry| entry.elapsed).sum()
+    }
+
+    pub fn matches(&self) -> impl Iterator<Item = &TraceEntry> {
+        self.entries.iter().filter(|entry| entry.matched)
+    }
+}
+
+impl Display for RuleTrace {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let header = ["#", "rule", "priority", "result", "time"].map(String::from);
+        let rows: Vec<[String; 5]> = self
+            .entries
+            .iter()
+            .map(|entry| {
+                [
+                    entry.index.to_string(),
+                    entry.label.clone().unwrap_or_else(|| "-".to_string()),
+                    entry.priority.to_string(),
+                    match entry.matched {
+                        true => "match".to_string(),
+                        false => "no match".to_string(),
+                    },
+                    format!("{:?}", entry.elapsed),
+                ]
+            })
+            .collect();
+
+        let mut widths = header.clone().map(|cell| cell.chars().count());
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        for row in std::iter::once(&header).chain(&rows) {
+            let cells: Vec<String> = row
+                .iter()
+                .zip(widths)
+                .map(|(cell, width)| format!("{cell:<width$}"))
+                .collect();
+            writeln!(f, "{}", cells.join("  ").trim_end())?;
+        }
+        write!(
+            f,
+            "{} of {} rules matched in {:?}",
+            self.matches().count(),
+            self.entries.len(),
+            self.total_elapsed()
+        )
+    }
+}
@@ -1,33 +1,154 @@
+use std::time::Instant;
+
+use crate::parsing::rules::{RuleTrace, TraceEntry};
+
 pub trait Rule {
     type Item: ?Sized;
-    type Result<'a> where Self: 'a;
+    type Result<'a>
+    where
+        Self: 'a;
 
     fn test<'a>(&'a self, eval: &'a Self::Item) -> Self::Result<'a>;
+
+    /// Only used to describe the rule, as in traces.
+    fn priority(&self) -> usize {
+        0
+    }
+
+    fn label(&self) -> Option<&str> {
+        None
+    }
+}
+
+/// The outcome of testing a rule, which may or may not be a match.
+pub trait RuleResult {
+    fn is_match(&self) -> bool;
 }
 
+/// Rules are kept highest priority first, and rules of equal priority stay in insertion order.
+/// Every method tests them in that order, and rule indices refer to it.
 pub trait RuleSet: Default {
     type Item: ?Sized;
-    type Result<'a> where Self: 'a;
+    type Result<'a>
+    where
+        Self: 'a;
     type Rule: Rule<Item = Self::Item>;
 
     fn get_rules(&self) -> &Vec<Self::Rule>;
     fn insert(&mut self, rule: Self::Rule);
 
     fn test_all<'a>(&'a self, obj: &'a Self::Item) -> Vec<<Self::Rule as Rule>::Result<'a>> {
-        self.get_rules()
+        self.get_rules().iter().map(|rule| rule.test(obj)).collect()
+    }
+
+    /// As `test_all`, recording every rule in a trace.
+    fn test_all_traced<'a>(
+        &'a self,
+        obj: &'a Self::Item,
+    ) -> (Vec<<Self::Rule as Rule>::Result<'a>>, RuleTrace)
+    where
+        <Self::Rule as Rule>::Result<'a>: RuleResult,
+    {
+        let mut trace = RuleTrace::default();
+        let results = self
+            .get_rules()
             .iter()
-            .map(|rule| rule.test(obj))
-            .collect()
+            .enumerate()
+            .map(|(index, rule)| traced_test(rule, index, obj, &mut trace))
+            .collect();
+        (results, trace)
     }
 
+    /// Stops testing at the first match, and gives the default result if nothing matches.
     fn first_match<'a>(&'a self, obj: &'a Self::Item) -> <Self::Rule as Rule>::Result<'a>
     where
-        <Self::Rule as Rule>::Result<'a>: Default,
+        <Self::Rule as Rule>::Result<'a>: RuleResult + Default,
     {
         self.get_rules()
             .iter()
             .map(|rule| rule.test(obj))
-            .find(|_| true)
+            .find(RuleResult::is_match)
             .unwrap_or_default()
     }
+
+    /// As `first_match`, recording the rules tested up to the match in a trace.
+    fn first_match_traced<'a>(
+        &'a self,
+        obj: &'a Self::Item,
+    ) -> (<Self::Rule as Rule>::Result<'a>, RuleTrace)
+    where
+        <Self::Rule as Rule>::Result<'a>: RuleResult + Default,
+    {
+        let mut trace = RuleTrace::default();
+        let result = self
+            .get_rules()
+            .iter()
+            .enumerate()
+            .map(|(index, rule)| traced_test(rule, index, obj, &mut trace))
+            .find(RuleResult::is_match)
+            .unwrap_or_default();
+        (result, trace)
+    }
+
+    /// The match with the greatest `key`, with ties going to the earlier rule.
+    fn best_match<'a, K: Ord>(
+        &'a self,
+        obj: &'a Self::Item,
+        mut key: impl FnMut(&<Self::Rule as Rule>::Result<'a>) -> K,
+    ) -> <Self::Rule as Rule>::Result<'a>
+    where
+        <Self::Rule as Rule>::Result<'a>: RuleResult + Default,
+    {
+        let mut best: Option<(K, <Self::Rule as Rule>::Result<'a>)> = None;
+        for result in self.get_rules().iter().map(|rule| rule.test(obj)) {
+            if !result.is_match() {
+                continue;
+            }
+            let result_key = key(&result);
+            if best
+                .as_ref()
+                .is_none_or(|(best_key, _)| result_key > *best_key)
+            {
+                best = Some((result_key, result));
+            }
+        }
+        best.map(|(_, result)| result).unwrap_or_default()
+    }
+
+    /// Every match, paired with the index of the rule that made it.
+    fn all_matches<'a>(
+        &'a self,
+        obj: &'a Self::Item,
+    ) -> Vec<(usize, <Self::Rule as Rule>::Result<'a>)>
+    where
+        <Self::Rule as Rule>::Result<'a>: RuleResult,
+    {
+        self.get_rules()
+            .iter()
+            .map(|rule| rule.test(obj))
+            .enumerate()
+            .filter(|(_, result)| result.is_match())
+            .collect()
+    }
+}
+
+fn traced_test<'a, R: Rule>(
+    rule: &'a R,
+    index: usize,
+    obj: &'a R::Item,
+    trace: &mut RuleTrace,
+) -> R::Result<'a>
+where
+    R::Result<'a>: RuleResult,
+{
+    let start = Instant::now();
+    let result = rule.test(obj);
+    trace.push(TraceEntry {
+        index,
+        label: rule.label().map(str::to_string),
+        priority: rule.priority(),
+        matched: result.is_match(),
+        elapsed: start.elapsed(),
+    });
+    result
 }
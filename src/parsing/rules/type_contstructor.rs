@@ -1,3 +1,42 @@
+use std::marker::PhantomData;
+
+use crate::parsing::rules::TaggedMatch;
+
 pub trait TypeConstructor {
     type Of<'a>;
 }
+
+/// Rules that match a borrowed part of a `str`.
+pub struct StrSlice;
+
+/// Rules with nothing to return but whether they matched.
+pub struct Unit;
+
+/// Rules that always match, with the inner result when there is one.
+pub struct Optional<R>(PhantomData<R>);
+
+/// Rules that produce a token and the number of bytes of input it used, as a `Lexer` needs.
+pub struct Lexeme<T>(PhantomData<T>);
+
+/// Rules loaded from a rule file.
+pub struct Tagged;
+
+impl TypeConstructor for StrSlice {
+    type Of<'a> = &'a str;
+}
+
+impl TypeConstructor for Unit {
+    type Of<'a> = ();
+}
+
+impl<R: TypeConstructor> TypeConstructor for Optional<R> {
+    type Of<'a> = Option<R::Of<'a>>;
+}
+
+impl<T> TypeConstructor for Lexeme<T> {
+    type Of<'a> = (T, usize);
+}
+
+impl TypeConstructor for Tagged {
+    type Of<'a> = TaggedMatch<'a>;
+}
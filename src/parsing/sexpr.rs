@@ -0,0 +1,344 @@
+use std::fmt::Display;
+
+use thiserror::Error;
+
+use crate::extensions::{DelimiterError, DelimiterSet, StringExt};
+use crate::parsing::rules::{RuleMap, RuleMapError};
+//
+//      ERRORS
+//
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum ReadError {
+    #[error("Input is incomplete, from byte {0}.")]
+    IncompleteError(usize),
+    #[error("Unexpected {found:?} at byte {position}.")]
+    UnexpectedError { found: char, position: usize },
+    #[error("Invalid escape {found:?} at byte {position}.")]
+    EscapeError { found: char, position: usize },
+}
+
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum EvalError {
+    #[error("Can't call {0}.")]
+    NotCallableError(String),
+    #[error("({head} ...): {error}")]
+    RuleError { head: String, error: RuleMapError },
+}
+//
+//      STRUCTS
+//
+#[derive(Clone, Debug, PartialEq)]
+pub enum SExpr {
+    Atom(String),
+    Number(f64),
+    Str(String),
+    List(Vec<SExpr>),
+}
+
+/// Evaluates lists by calling the rule named by their head atom with their evaluated tail.
+/// Everything else, including the empty list, evaluates to itself.
+pub struct SExprEvaluator {
+    rules: RuleMap<String, SExpr, SExpr>,
+}
+
+/// Collects lines until they hold complete expressions, for reading a line at a time.
+#[derive(Clone, Debug, Default)]
+pub struct ReplBuffer {
+    buffer: String,
+}
+
+struct SExprReader<'a> {
+    source: &'a str,
+    position: usize,
+    end: usize,
+}
+//
+//      STRUCT IMPLS
+//
+impl ReadError {
+    /// Whether more input could still make this read succeed.
+    pub fn is_incomplete(&self) -> bool {
+        matches!(self, ReadError::IncompleteError(_))
+    }
+}
+
+impl SExpr {
+    /// Reads exactly one expression. Empty input is incomplete.
+    pub fn read(source: &str) -> Result<SExpr, ReadError> {
+        SExprReader::new(source).read_single()
+    }
+
+    /// Reads every expression, separated by whitespace.
+    pub fn read_all(source: &str) -> Result<Vec<SExpr>, ReadError> {
+        SExprReader::new(source).read_all()
+    }
+
+    pub fn as_atom(&self) -> Option<&str> {
+        match self {
+            SExpr::Atom(name) => Some(name),
+            _ => None,
+        }
+    }
+
+    pub fn as_number(&self) -> Option<f64> {
+        match self {
+            SExpr::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    pub fn as_list(&self) -> Option<&[SExpr]> {
+        match self {
+            SExpr::List(items) => Some(items),
+            _ => None,
+        }
+    }
+}
+
+impl SExprEvaluator {
+    pub fn new(rules: RuleMap<String, SExpr, SExpr>) -> Self {
+        Self { rules }
+    }
+
+    pub fn rules(&self) -> &RuleMap<String, SExpr, SExpr> {
+        &self.rules
+    }
+
+    pub fn rules_mut(&mut self) -> &mut RuleMap<String, SExpr, SExpr> {
+        &mut self.rules
+    }
+
+    pub fn evaluate(&self, expr: &SExpr) -> Result<SExpr, EvalError> {
+        let Some((head, tail)) = expr.as_list().and_then(<[SExpr]>::split_first) else {
+            return Ok(expr.clone());
+        };
+        let Some(name) = head.as_atom() else {
+            return Err(EvalError::NotCallableError(head.to_string()));
+        };
+
+        let args = tail
+            .iter()
+            .map(|arg| self.evaluate(arg))
+            .collect::<Result<Vec<SExpr>, EvalError>>()?;
+        self.rules
+            .evaluate(name, &args)
+            .map_err(|error| EvalError::RuleError {
+                head: name.to_string(),
+                error,
+            })
+    }
+}
+
+impl ReplBuffer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a line and reads the buffer. `Ok(None)` means more lines are needed. Otherwise the
+    /// buffer is cleared, so an error doesn't stick to later input.
+    pub fn push_line(&mut self, line: &str) -> Result<Option<Vec<SExpr>>, ReadError> {
+        self.buffer.push_str(line);
+        self.buffer.push('\n');
+
+        match SExpr::read_all(&self.buffer) {
+            Ok(exprs) if exprs.is_empty() => Ok(None),
+            Err(error) if error.is_incomplete() => Ok(None),
+            result => {
+                self.buffer.clear();
+                result.map(Some)
+            }
+        }
+    }
+
+    /// Whether lines are waiting on the rest of an expression.
+    pub fn is_pending(&self) -> bool {
+        !self.buffer.trim().is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.buffer.clear();
+    }
+}
+
+impl<'a> SExprReader<'a> {
+    fn new(source: &'a str) -> Self {
+        Self {
+            source,
+            position: 0,
+            end: source.len(),
+        }
+    }
+    //
+    //      PRIVATE
+    //
+    fn rest(&self) -> &'a str {
+        &self.source[self.position..self.end]
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.position += rest.len() - rest.trim_start().len();
+    }
+
+    fn delimiters() -> DelimiterSet {
+        DelimiterSet::new(&[('(', ')')])
+            .with_quotes(&['"'])
+            .with_escape(Some('\\'))
+    }
+    //
+    //      GRAMMAR
+    //
+    fn read_all(mut self) -> Result<Vec<SExpr>, ReadError> {
+        let mut exprs = vec![];
+        loop {
+            self.skip_whitespace();
+            if self.position >= self.end {
+                return Ok(exprs);
+            }
+            exprs.push(self.read_one()?);
+        }
+    }
+
+    fn read_single(mut self) -> Result<SExpr, ReadError> {
+        self.skip_whitespace();
+        let expr = self.read_one()?;
+        self.skip_whitespace();
+        match self.rest().chars().next() {
+            Some(found) => Err(ReadError::UnexpectedError {
+                found,
+                position: self.position,
+            }),
+            None => Ok(expr),
+        }
+    }
+
+    fn read_one(&mut self) -> Result<SExpr, ReadError> {
+        let start = self.position;
+        match self.rest().chars().next() {
+            Some('(') => {
+                let close = self
+                    .source
+                    .find_matching_delimiter(start, &Self::delimiters())
+                    .map_err(ReadError::from)?
+                    .ok_or(ReadError::IncompleteError(start))?;
+                let items = SExprReader {
+                    source: self.source,
+                    position: start + 1,
+                    end: close,
+                }
+                .read_all()?;
+                self.position = close + 1;
+                Ok(SExpr::List(items))
+            }
+            Some('"') => self.read_string(),
+            Some(')') => Err(ReadError::UnexpectedError {
+                found: ')',
+                position: start,
+            }),
+            Some(_) => Ok(self.read_atom()),
+            None => Err(ReadError::IncompleteError(start)),
+        }
+    }
+
+    fn read_string(&mut self) -> Result<SExpr, ReadError> {
+        let start = self.position;
+        let mut text = String::new();
+        let mut chars = self.rest().char_indices().skip(1);
+
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.position = start + i + 1;
+                    return Ok(SExpr::Str(text));
+                }
+                '\\' => {
+                    let Some((j, escaped)) = chars.next() else {
+                        break;
+                    };
+                    text.push(match escaped {
+                        'n' => '\n',
+                        't' => '\t',
+                        'r' => '\r',
+                        '\\' | '"' => escaped,
+                        found => {
+                            return Err(ReadError::EscapeError {
+                                found,
+                                position: start + j,
+                            });
+                        }
+                    });
+                }
+                c => text.push(c),
+            }
+        }
+        Err(ReadError::IncompleteError(start))
+    }
+
+    fn read_atom(&mut self) -> SExpr {
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| c.is_whitespace() || matches!(c, '(' | ')' | '"'))
+            .unwrap_or(rest.len());
+        let text = &rest[..len];
+        self.position += len;
+
+        // keeps names like `inf`, `nan` and `-` as atoms
+        let numeric = text
+            .trim_start_matches(['+', '-'])
+            .starts_with(|c: char| c.is_ascii_digit() || c == '.');
+        match text.parse() {
+            Ok(n) if numeric => SExpr::Number(n),
+            _ => SExpr::Atom(text.to_string()),
+        }
+    }
+}
+//
+//      TRAIT IMPLS
+//
+impl From<DelimiterError> for ReadError {
+    fn from(error: DelimiterError) -> Self {
+        match error {
+            DelimiterError::UnclosedError { position, .. }
+            | DelimiterError::UnterminatedQuoteError { position, .. } => {
+                ReadError::IncompleteError(position)
+            }
+            DelimiterError::UnexpectedCloseError { found, position }
+            | DelimiterError::MismatchError {
+                found, position, ..
+            } => ReadError::UnexpectedError { found, position },
+        }
+    }
+}
+
+/// Writes the expression back in a form `SExpr::read` reads.
+impl Display for SExpr {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            SExpr::Atom(name) => write!(f, "{name}"),
+            SExpr::Number(n) => write!(f, "{n}"),
+            SExpr::Str(text) => {
+                write!(f, "\"")?;
+                for c in text.chars() {
+                    match c {
+                        '"' | '\\' => write!(f, "\\{c}")?,
+                        '\n' => write!(f, "\\n")?,
+                        '\t' => write!(f, "\\t")?,
+                        '\r' => write!(f, "\\r")?,
+                        c => write!(f, "{c}")?,
+                    }
+                }
+                write!(f, "\"")
+            }
+            SExpr::List(items) => {
+                write!(f, "(")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, " ")?;
+                    }
+                    write!(f, "{item}")?;
+                }
+                write!(f, ")")
+            }
+        }
+    }
+}
@@ -1,19 +1,303 @@
-use std::{fmt::Display, hash::Hash};
+use std::fmt::{Display, Write};
+//
+//      STRUCTS
+//
+/// Splits text on every occurrence of an indicator string.
+///
+/// By default the indicator is dropped, empty pieces are kept and there is no limit on the
+/// number of splits, which matches `str::split`. A kept indicator ends the piece before it.
+/// Skipped empty pieces still count towards `max_splits`.
+#[derive(Clone, Debug)]
+pub struct Splitter {
+    indicator: String,
+    keep_separator: bool,
+    skip_empty: bool,
+    max_splits: Option<usize>,
+}
 
-trait DisplayIter: Iterator<Item: Display> {}
-impl<A: Iterator<Item: Display>> DisplayIter for A {}
+/// Lazy pieces of a `&str`, borrowed from the text rather than the splitter.
+pub struct Split<'s, 't> {
+    splitter: &'s Splitter,
+    rest: Option<&'t str>,
+    splits: usize,
+}
 
+/// Lazy pieces of a `Display` stream. Items are rendered in order as one continuous text, so
+/// an indicator can span several of them.
+pub struct DisplaySplit<'a, I> {
+    splitter: &'a Splitter,
+    stream: I,
+    buffer: String,
+    searched: usize,
+    splits: usize,
+    done: bool,
+}
+//
+//      STRUCT IMPLS
+//
+impl Splitter {
+    //
+    //      PRIVATE
+    //
+    fn limit_reached(&self, splits: usize) -> bool {
+        self.max_splits.is_some_and(|max| splits >= max)
+    }
 
-pub struct DisplaySplitter {
-    indicator: String,
+    /// The piece ending at the indicator found at `index`, and whether it has no content.
+    fn piece<'t>(&self, text: &'t str, index: usize) -> (&'t str, bool) {
+        let end = match self.keep_separator {
+            true => index + self.indicator.len(),
+            false => index,
+        };
+        (&text[..end], index == 0)
+    }
+    //
+    //      CONSTRUCTORS
+    //
+    /// Panics if `indicator` displays as an empty string.
+    pub fn new(indicator: impl Display) -> Splitter {
+        let indicator = indicator.to_string();
+        assert!(
+            !indicator.is_empty(),
+            "splitter indicator must not be empty"
+        );
+        Splitter {
+            indicator,
+            keep_separator: false,
+            skip_empty: false,
+            max_splits: None,
+        }
+    }
+
+    pub fn keep_separator(mut self, keep: bool) -> Self {
+        self.keep_separator = keep;
+        self
+    }
+
+    pub fn skip_empty(mut self, skip: bool) -> Self {
+        self.skip_empty = skip;
+        self
+    }
+
+    /// After `max` splits the rest of the text is returned as the last piece.
+    pub fn max_splits(mut self, max: usize) -> Self {
+        self.max_splits = Some(max);
+        self
+    }
+    //
+    //      SPLITTING
+    //
+    pub fn indicator(&self) -> &str {
+        &self.indicator
+    }
+
+    pub fn split<'t>(&self, text: &'t str) -> Split<'_, 't> {
+        Split {
+            splitter: self,
+            rest: Some(text),
+            splits: 0,
+        }
+    }
+
+    pub fn split_collect<'t>(&self, text: &'t str) -> Vec<&'t str> {
+        self.split(text).collect()
+    }
+
+    pub fn split_display<I: IntoIterator<Item: Display>>(
+        &self,
+        stream: I,
+    ) -> DisplaySplit<'_, I::IntoIter> {
+        DisplaySplit {
+            splitter: self,
+            stream: stream.into_iter(),
+            buffer: String::new(),
+            searched: 0,
+            splits: 0,
+            done: false,
+        }
+    }
+
+    pub fn split_display_collect<I: IntoIterator<Item: Display>>(&self, stream: I) -> Vec<String> {
+        self.split_display(stream).collect()
+    }
+}
+//
+//      ITERATOR IMPLS
+//
+impl<'t> Iterator for Split<'_, 't> {
+    type Item = &'t str;
+
+    fn next(&mut self) -> Option<&'t str> {
+        let splitter = self.splitter;
+        loop {
+            let rest = self.rest?;
+            let found = match splitter.limit_reached(self.splits) {
+                true => None,
+                false => rest.find(&splitter.indicator),
+            };
+
+            let Some(index) = found else {
+                self.rest = None;
+                if splitter.skip_empty && rest.is_empty() {
+                    return None;
+                }
+                return Some(rest);
+            };
+
+            let (piece, empty) = splitter.piece(rest, index);
+            self.rest = Some(&rest[index + splitter.indicator.len()..]);
+            self.splits += 1;
+            if !(splitter.skip_empty && empty) {
+                return Some(piece);
+            }
+        }
+    }
 }
 
-impl DisplaySplitter {
-    pub fn new(indicator: impl Display) -> DisplaySplitter {
-        DisplaySplitter {
-            indicator: indicator.to_string(),
+impl<I: Iterator<Item: Display>> Iterator for DisplaySplit<'_, I> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        let splitter = self.splitter;
+        let indicator = &splitter.indicator;
+        while !self.done {
+            let limited = splitter.limit_reached(self.splits);
+
+            if !limited && let Some(index) = self.buffer[self.searched..].find(indicator.as_str()) {
+                let index = self.searched + index;
+                let (piece, empty) = splitter.piece(&self.buffer, index);
+                let piece = piece.to_string();
+
+                self.buffer.drain(..index + indicator.len());
+                self.searched = 0;
+                self.splits += 1;
+                if !(splitter.skip_empty && empty) {
+                    return Some(piece);
+                }
+                continue;
+            }
+
+            // only the tail of the buffer could start an indicator that the next item finishes
+            let mut searched = self.buffer.len().saturating_sub(indicator.len() - 1);
+            while !self.buffer.is_char_boundary(searched) {
+                searched -= 1;
+            }
+            self.searched = searched;
+
+            match self.stream.next() {
+                Some(item) => {
+                    write!(self.buffer, "{item}").expect("writing to a String can't fail")
+                }
+                None => {
+                    self.done = true;
+                    let piece = std::mem::take(&mut self.buffer);
+                    if !(splitter.skip_empty && piece.is_empty()) {
+                        return Some(piece);
+                    }
+                }
+            }
         }
+        None
     }
+}
+//
+//      TESTS
+//
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pieces(text: &str) -> Vec<&str> {
+        Splitter::new(",").split_collect(text)
+    }
+
+    #[test]
+    fn pieces_outlive_the_splitter() {
+        assert_eq!(pieces("a,b"), ["a", "b"]);
 
-    pub fn split
+        let text = String::from("x--y");
+        let first = {
+            let splitter = Splitter::new("--");
+            splitter.split(&text).next()
+        };
+        assert_eq!(first, Some("x"));
+    }
+
+    #[test]
+    fn defaults_match_str_split() {
+        for text in ["", ",", "a", "a,b", ",a,,b,", "é,中,😀"] {
+            assert_eq!(
+                pieces(text),
+                text.split(',').collect::<Vec<_>>(),
+                "{text:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn keep_separator_ends_each_piece() {
+        let splitter = Splitter::new("; ").keep_separator(true);
+        assert_eq!(splitter.split_collect("a; b; c"), ["a; ", "b; ", "c"]);
+        assert_eq!(splitter.split_collect("a; "), ["a; ", ""]);
+        assert_eq!(splitter.split_collect("; a"), ["; ", "a"]);
+    }
+
+    #[test]
+    fn skip_empty_drops_empty_pieces() {
+        let splitter = Splitter::new(",").skip_empty(true);
+        assert_eq!(splitter.split_collect(",a,,b,"), ["a", "b"]);
+        assert_eq!(splitter.split_collect(""), Vec::<&str>::new());
+        assert_eq!(splitter.split_collect(",,"), Vec::<&str>::new());
+
+        // a kept separator alone is still empty
+        let splitter = splitter.keep_separator(true);
+        assert_eq!(splitter.split_collect(",a,,b"), ["a,", "b"]);
+    }
+
+    #[test]
+    fn max_splits_leaves_the_rest_whole() {
+        let splitter = Splitter::new(",").max_splits(2);
+        assert_eq!(splitter.split_collect("a,b,c,d"), ["a", "b", "c,d"]);
+        assert_eq!(splitter.split_collect("a"), ["a"]);
+        assert_eq!(
+            Splitter::new(",").max_splits(0).split_collect("a,b"),
+            ["a,b"]
+        );
+
+        // skipped pieces count towards the limit
+        let splitter = splitter.skip_empty(true);
+        assert_eq!(splitter.split_collect(",,a,b"), ["a,b"]);
+    }
+
+    #[test]
+    fn display_indicators_span_items() {
+        let splitter = Splitter::new("<>");
+        assert_eq!(
+            splitter.split_display_collect(["a<", ">b<", "", ">", "c"]),
+            ["a", "b", "c"]
+        );
+        assert_eq!(splitter.split_display_collect([1, 2, 3]), ["123"]);
+        assert_eq!(
+            Splitter::new(0).split_display_collect([10, 20, 3]),
+            ["1", "2", "3"]
+        );
+        assert_eq!(
+            Splitter::new("é").split_display_collect(["aé", "b", "é"]),
+            ["a", "b", ""]
+        );
+    }
+
+    #[test]
+    fn display_split_honours_options() {
+        let stream = ["a,", ",b", ",c,d"];
+        let splitter = Splitter::new(",,").keep_separator(true);
+        assert_eq!(splitter.split_display_collect(stream), ["a,,", "b,c,d"]);
+
+        let splitter = Splitter::new(",").skip_empty(true).max_splits(3);
+        assert_eq!(splitter.split_display_collect(stream), ["a", "b", "c,d"]);
+        assert_eq!(
+            Splitter::new(",").split_display_collect(Vec::<&str>::new()),
+            [""]
+        );
+    }
 }